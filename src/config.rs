@@ -0,0 +1,294 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use export::ExportFormat;
+use storage::config_dir;
+use ui::Action;
+
+/// The colors of the terminal theme that parts of a matrix can be drawn in.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ColorRole {
+    Primary,
+    Secondary,
+    Tertiary,
+    TitlePrimary,
+    TitleSecondary,
+    Highlight,
+    HighlightInactive
+}
+
+impl ColorRole {
+
+    pub fn all() -> Vec<ColorRole> {
+        vec![ColorRole::Primary, ColorRole::Secondary, ColorRole::Tertiary, ColorRole::TitlePrimary,
+             ColorRole::TitleSecondary, ColorRole::Highlight, ColorRole::HighlightInactive]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            ColorRole::Primary => "primary",
+            ColorRole::Secondary => "secondary",
+            ColorRole::Tertiary => "tertiary",
+            ColorRole::TitlePrimary => "title_primary",
+            ColorRole::TitleSecondary => "title_secondary",
+            ColorRole::Highlight => "highlight",
+            ColorRole::HighlightInactive => "highlight_inactive"
+        }
+    }
+}
+
+impl FromStr for ColorRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<ColorRole, String> {
+        ColorRole::all()
+            .into_iter()
+            .find(|role| role.name() == s)
+            .ok_or_else(|| format!("unknown color: {}", s))
+    }
+}
+
+/// Which color each part of a `MatrixView` is drawn in.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Colors {
+    pub entries: ColorRole,
+    pub pivots: ColorRole,
+    pub zeros: ColorRole,
+    /// Cells changed by the last command.
+    pub changes: ColorRole,
+    /// Row and column labels.
+    pub labels: ColorRole,
+    /// The command shown next to the rows it operated on.
+    pub notes: ColorRole
+}
+
+impl Colors {
+
+    /// The built in themes are `default`, `plain`, which only highlights
+    /// changes, and `contrast`.
+    pub fn theme(name: &str) -> Option<Colors> {
+        let default = Colors {
+            entries: ColorRole::Primary,
+            pivots: ColorRole::TitlePrimary,
+            zeros: ColorRole::Secondary,
+            changes: ColorRole::Highlight,
+            labels: ColorRole::TitleSecondary,
+            notes: ColorRole::Tertiary
+        };
+        match name {
+            "default" => Some(default),
+            "plain" => Some(Colors {
+                pivots: ColorRole::Primary,
+                zeros: ColorRole::Primary,
+                labels: ColorRole::Primary,
+                notes: ColorRole::Primary,
+                ..default
+            }),
+            "contrast" => Some(Colors {
+                pivots: ColorRole::Highlight,
+                changes: ColorRole::HighlightInactive,
+                labels: ColorRole::TitlePrimary,
+                ..default
+            }),
+            _ => None
+        }
+    }
+}
+
+impl Default for Colors {
+    fn default() -> Colors {
+        Colors::theme("default").unwrap()
+    }
+}
+
+/// How numbers are shown in the matrix.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Notation {
+    /// Exact fractions such as `-1/3`.
+    Fraction,
+    /// Decimals rounded to the configured precision. Calculations are still
+    /// exact.
+    Decimal
+}
+
+/// The settings read from the config file.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Config {
+    /// Keys that differ from the default ones.
+    keys: HashMap<Action, char>,
+    pub colors: Colors,
+    pub notation: Notation,
+    /// Digits after the decimal point in decimal notation.
+    pub precision: usize,
+    pub export_format: ExportFormat,
+    /// Whether exports start with the pivot columns and subspace bases
+    /// included.
+    pub export_subspaces: bool
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            keys: HashMap::new(),
+            colors: Colors::default(),
+            notation: Notation::Fraction,
+            precision: 3,
+            export_format: ExportFormat::Plain,
+            export_subspaces: false
+        }
+    }
+}
+
+impl Config {
+
+    /// The key that performs the action.
+    pub fn key(&self, action: Action) -> char {
+        self.keys.get(&action).cloned().unwrap_or_else(|| action.default_key())
+    }
+
+    /// Binds the action to a key, failing if another action already uses
+    /// it.
+    pub fn set_key(&mut self, action: Action, key: char) -> Result<(), String> {
+        let taken = Action::all().into_iter().find(|&other| other != action && self.key(other) == key);
+        if let Some(other) = taken {
+            return Err(format!("{} is already bound to {}", key, other.name()));
+        }
+        self.keys.insert(action, key);
+        Ok(())
+    }
+
+    /// Every action with its key, in the order of `Action::all`.
+    pub fn bindings(&self) -> Vec<(Action, char)> {
+        Action::all().into_iter().map(|action| (action, self.key(action))).collect()
+    }
+
+    /// Reads a config file like the following, where every setting is
+    /// optional:
+    ///
+    /// ```text
+    /// [keys]
+    /// scale = "m"
+    ///
+    /// [colors]
+    /// theme = "contrast"
+    /// zeros = "tertiary"
+    ///
+    /// [numbers]
+    /// notation = "decimal"
+    /// precision = 4
+    ///
+    /// [export]
+    /// format = "latex"
+    /// subspaces = true
+    /// ```
+    pub fn parse(text: &str) -> Result<Config, String> {
+        let mut config = Config::default();
+        // Keys are set after every other binding is known, so that two
+        // actions can trade keys.
+        let mut keys = Vec::new();
+        let mut color_overrides = Vec::new();
+        let mut section = String::new();
+
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+            let at_line = |e: String| format!("line {}: {}", i + 1, e);
+            if line.starts_with('[') && line.ends_with(']') {
+                section = line[1..(line.len() - 1)].trim().to_owned();
+                continue;
+            }
+            let (name, value) = match line.find('=') {
+                Some(eq) => (line[..eq].trim(), unquote(line[(eq + 1)..].trim())),
+                None => return Err(at_line(format!("expected name = value, found {}", line)))
+            };
+            let unknown = || at_line(format!("unknown setting {} in [{}]", name, section));
+            match (section.as_str(), name) {
+                ("keys", _) => {
+                    let action = Action::from_name(name).ok_or_else(unknown)?;
+                    let mut chars = value.chars();
+                    match (chars.next(), chars.next()) {
+                        (Some(key), None) => keys.push((action, key)),
+                        _ => return Err(at_line(format!("the key for {} must be one character", name)))
+                    }
+                },
+                ("colors", "theme") => {
+                    config.colors = Colors::theme(value)
+                        .ok_or_else(|| at_line(format!("unknown theme: {}", value)))?;
+                },
+                ("colors", _) => {
+                    let role = ColorRole::from_str(value).map_err(&at_line)?;
+                    color_overrides.push((name.to_owned(), role, i + 1));
+                },
+                ("numbers", "notation") => {
+                    config.notation = match value {
+                        "fraction" => Notation::Fraction,
+                        "decimal" => Notation::Decimal,
+                        _ => return Err(at_line(format!("unknown notation: {}", value)))
+                    };
+                },
+                ("numbers", "precision") => {
+                    config.precision = usize::from_str(value)
+                        .map_err(|_| at_line(format!("invalid precision: {}", value)))?;
+                },
+                ("export", "format") => {
+                    config.export_format = ExportFormat::from_str(value).map_err(&at_line)?;
+                },
+                ("export", "subspaces") => {
+                    config.export_subspaces = bool::from_str(value)
+                        .map_err(|_| at_line(format!("subspaces must be true or false, not {}", value)))?;
+                },
+                _ => return Err(unknown())
+            }
+        }
+
+        // Colors override the theme no matter which comes first.
+        for (name, role, line) in color_overrides {
+            let colors = &mut config.colors;
+            match name.as_str() {
+                "entries" => colors.entries = role,
+                "pivots" => colors.pivots = role,
+                "zeros" => colors.zeros = role,
+                "changes" => colors.changes = role,
+                "labels" => colors.labels = role,
+                "notes" => colors.notes = role,
+                _ => return Err(format!("line {}: unknown setting {} in [colors]", line, name))
+            }
+        }
+
+        for (action, key) in keys {
+            config.keys.insert(action, key);
+        }
+        let bindings = config.bindings();
+        for (i, &(action, key)) in bindings.iter().enumerate() {
+            if let Some(&(other, _)) = bindings[..i].iter().find(|&&(_, k)| k == key) {
+                return Err(format!("{} is bound to both {} and {}", key, other.name(), action.name()));
+            }
+        }
+        Ok(config)
+    }
+
+    /// Reads the config file, using the defaults if there isn't one.
+    pub fn load(path: &Path) -> Result<Config, String> {
+        if !path.exists() {
+            return Ok(Config::default());
+        }
+        let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        Config::parse(&text)
+    }
+}
+
+/// Removes the quotes around a value, if it has them.
+fn unquote(value: &str) -> &str {
+    let quoted = value.len() >= 2 &&
+        ((value.starts_with('"') && value.ends_with('"')) || (value.starts_with('\'') && value.ends_with('\'')));
+    if quoted { &value[1..(value.len() - 1)] } else { value }
+}
+
+/// Where the config file is read from.
+pub fn config_path() -> Result<PathBuf, String> {
+    Ok(config_dir()?.join("config.toml"))
+}
@@ -0,0 +1,353 @@
+use num_complex::Complex;
+use num_rational::Ratio;
+use std::convert::TryFrom;
+
+use matrix::{Field, Matrix, MatrixResult};
+use polynomial::Polynomial;
+use reduce::row_reduce;
+
+/// An exact eigenvalue together with a basis of its eigenspace.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Eigenspace<T> {
+    pub value: T,
+    /// The multiplicity of the eigenvalue as a root of the characteristic
+    /// polynomial.
+    pub multiplicity: usize,
+    pub basis: Vec<Vec<T>>
+}
+
+/// Maximum number of QR iterations spent on a single eigenvalue.
+const MAX_ITERATIONS: usize = 60;
+
+impl <T: Field> Matrix<T> {
+
+    /// Computes a basis of the eigenspace of the given eigenvalue by row
+    /// reducing `A - λI`. The basis is empty if `value` is not an eigenvalue.
+    pub fn eigenvectors(&self, value: &T) -> MatrixResult<Vec<Vec<T>>> {
+        if !self.is_square() {
+            return Err(format!("a {}x{} matrix has no eigenvectors", self.rows, self.cols));
+        }
+        let mut shifted = self.clone();
+        for i in 1..(self.rows + 1) {
+            let diag = shifted.get(i, i).unwrap() - value.clone();
+            shifted.set(i, i, diag)?;
+        }
+        Ok(row_reduce(&shifted)?.kernel_basis())
+    }
+}
+
+impl Matrix<Ratio<i64>> {
+
+    /// Computes the characteristic polynomial `det(xI - A)` exactly using the
+    /// Faddeev-LeVerrier algorithm. Fails if the numbers involved get too large.
+    pub fn characteristic_polynomial(&self) -> MatrixResult<Polynomial<Ratio<i64>>> {
+        if !self.is_square() {
+            return Err(format!("a {}x{} matrix has no characteristic polynomial", self.rows, self.cols));
+        }
+        let overflow = || "the characteristic polynomial is too large to compute".to_owned();
+        let n = self.rows;
+
+        // Scaling the matrix by a common denominator `d` gives an integer
+        // matrix `B = dA`, whose characteristic polynomial only needs integer
+        // arithmetic. Its coefficients `c_k` give `A`'s as `c_k / d^(n - k)`.
+        let rows = self.rows();
+        let d = rows.iter()
+            .flat_map(|row| row.iter())
+            .try_fold(1, |acc, x| lcm(acc, i128::from(*x.denom())))
+            .ok_or_else(overflow)?;
+        let b: Vec<Vec<i128>> = rows.iter()
+            .map(|row| row.iter().map(|x| i128::from(*x.numer()) * (d / i128::from(*x.denom()))).collect())
+            .collect();
+        let mut c = vec![0i128; n + 1];
+        c[n] = 1;
+        let mut m = vec![vec![0i128; n]; n];
+        for step in 1..(n + 1) {
+            m = checked_product(&b, &m).ok_or_else(overflow)?;
+            for (i, row) in m.iter_mut().enumerate() {
+                row[i] = row[i].checked_add(c[n - step + 1]).ok_or_else(overflow)?;
+            }
+            let bm = checked_product(&b, &m).ok_or_else(overflow)?;
+            let trace = (0..n).try_fold(0i128, |acc, i| acc.checked_add(bm[i][i])).ok_or_else(overflow)?;
+            // The trace is always a multiple of `step`.
+            c[n - step] = -trace / step as i128;
+        }
+
+        let coeffs = (0..(n + 1))
+            .map(|k| {
+                let scale = d.checked_pow((n - k) as u32)?;
+                let g = gcd(c[k], scale);
+                Some(Ratio::new(i64::try_from(c[k] / g).ok()?, i64::try_from(scale / g).ok()?))
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(overflow)?;
+        Ok(Polynomial::new(coeffs))
+    }
+
+    /// Finds every rational eigenvalue and computes a basis for its
+    /// eigenspace exactly.
+    pub fn rational_eigenspaces(&self) -> MatrixResult<Vec<Eigenspace<Ratio<i64>>>> {
+        let poly = self.characteristic_polynomial()?;
+        let mut spaces = Vec::new();
+        for (value, multiplicity) in poly.rational_roots()? {
+            let basis = self.eigenvectors(&value)?;
+            spaces.push(Eigenspace {
+                value,
+                multiplicity,
+                basis
+            });
+        }
+        Ok(spaces)
+    }
+}
+
+impl Matrix<f64> {
+
+    /// Approximates every eigenvalue, including complex ones, by reducing the
+    /// matrix to upper Hessenberg form and running the shifted QR algorithm.
+    pub fn eigenvalues(&self) -> MatrixResult<Vec<Complex<f64>>> {
+        if !self.is_square() {
+            return Err(format!("a {}x{} matrix has no eigenvalues", self.rows, self.cols));
+        }
+        // The algorithms below use 1-based indices, so the first row and
+        // column of `a` are unused padding.
+        let n = self.rows;
+        let mut a: Vec<Vec<f64>> = (0..(n + 1))
+            .map(|i| (0..(n + 1)).map(|j| self.get(i, j).unwrap_or(0.0)).collect())
+            .collect();
+        reduce_to_hessenberg(&mut a, n);
+        hessenberg_qr(&mut a, n)
+    }
+}
+
+/// The product of two square integer matrices, or `None` if it overflows.
+fn checked_product(a: &[Vec<i128>], b: &[Vec<i128>]) -> Option<Vec<Vec<i128>>> {
+    let n = a.len();
+    (0..n)
+        .map(|i| (0..n).map(|j| (0..n).try_fold(0i128, |acc, k| acc.checked_add(a[i][k].checked_mul(b[k][j])?))).collect())
+        .collect()
+}
+
+fn gcd(a: i128, b: i128) -> i128 {
+    if b == 0 { a.abs() } else { gcd(b, a % b) }
+}
+
+/// The least common multiple of two positive numbers, or `None` if it
+/// overflows.
+fn lcm(a: i128, b: i128) -> Option<i128> {
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// Reduces `a` to upper Hessenberg form by elimination with pivoting, which
+/// preserves the eigenvalues.
+fn reduce_to_hessenberg(a: &mut [Vec<f64>], n: usize) {
+    for m in 2..n {
+        let mut x: f64 = 0.0;
+        let mut pivot = m;
+        for (j, row) in a.iter().enumerate().skip(m) {
+            if row[m - 1].abs() > x.abs() {
+                x = row[m - 1];
+                pivot = j;
+            }
+        }
+        if pivot != m {
+            // Both rows are already zero left of column m - 1, so swapping
+            // them whole is the same as swapping from there on.
+            a.swap(pivot, m);
+            for row in a.iter_mut().skip(1) {
+                row.swap(pivot, m);
+            }
+        }
+        if x != 0.0 {
+            for i in (m + 1)..(n + 1) {
+                let y = a[i][m - 1] / x;
+                if y != 0.0 {
+                    a[i][m - 1] = 0.0;
+                    let (upper, lower) = a.split_at_mut(i);
+                    for (entry, pivot_entry) in lower[0][m..].iter_mut().zip(&upper[m][m..]) {
+                        *entry -= y * pivot_entry;
+                    }
+                    for row in a.iter_mut().skip(1) {
+                        row[m] += y * row[i];
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Finds the eigenvalues of an upper Hessenberg matrix using the Francis
+/// double shift QR algorithm, deflating one or two eigenvalues at a time from
+/// the bottom of the matrix.
+fn hessenberg_qr(a: &mut [Vec<f64>], n: usize) -> MatrixResult<Vec<Complex<f64>>> {
+    let mut values = vec![Complex::new(0.0, 0.0); n + 1];
+    let mut anorm: f64 = 0.0;
+    for (i, row) in a.iter().enumerate().skip(1) {
+        anorm += row[::std::cmp::max(i - 1, 1)..].iter().map(|x| x.abs()).sum::<f64>();
+    }
+
+    let mut nn = n;
+    // Accumulated exceptional shifts.
+    let mut t: f64 = 0.0;
+    while nn >= 1 {
+        let mut its = 0;
+        loop {
+            // Look for a negligible subdiagonal element to split the matrix.
+            let mut l = nn;
+            while l >= 2 {
+                let mut s = a[l - 1][l - 1].abs() + a[l][l].abs();
+                if s == 0.0 {
+                    s = anorm;
+                }
+                if a[l][l - 1].abs() + s == s {
+                    a[l][l - 1] = 0.0;
+                    break;
+                }
+                l -= 1;
+            }
+
+            let mut x = a[nn][nn];
+            if l == nn {
+                // One root found.
+                values[nn] = Complex::new(x + t, 0.0);
+                nn -= 1;
+                break;
+            }
+
+            let mut y = a[nn - 1][nn - 1];
+            let mut w = a[nn][nn - 1] * a[nn - 1][nn];
+            if l == nn - 1 {
+                // Two roots found from the trailing 2x2 block.
+                let p = 0.5 * (y - x);
+                let q = p * p + w;
+                let z = q.abs().sqrt();
+                x += t;
+                if q >= 0.0 {
+                    let z = p + z.copysign(p);
+                    values[nn - 1] = Complex::new(x + z, 0.0);
+                    values[nn] = if z != 0.0 {
+                        Complex::new(x - w / z, 0.0)
+                    } else {
+                        Complex::new(x + z, 0.0)
+                    };
+                } else {
+                    values[nn - 1] = Complex::new(x + p, -z);
+                    values[nn] = Complex::new(x + p, z);
+                }
+                nn -= 2;
+                break;
+            }
+
+            if its == MAX_ITERATIONS {
+                return Err("eigenvalue computation did not converge".to_owned());
+            }
+            if its == 10 || its == 20 {
+                // Exceptional shift to break out of cycles.
+                t += x;
+                for (i, row) in a.iter_mut().enumerate().take(nn + 1).skip(1) {
+                    row[i] -= x;
+                }
+                let s = a[nn][nn - 1].abs() + a[nn - 1][nn - 2].abs();
+                x = 0.75 * s;
+                y = x;
+                w = -0.4375 * s * s;
+            }
+            its += 1;
+
+            // Form the shift and look for two consecutive small subdiagonal
+            // elements.
+            let (mut p, mut q, mut r);
+            let mut m = nn - 2;
+            loop {
+                let z = a[m][m];
+                let rr = x - z;
+                let ss = y - z;
+                p = (rr * ss - w) / a[m + 1][m] + a[m][m + 1];
+                q = a[m + 1][m + 1] - z - rr - ss;
+                r = a[m + 2][m + 1];
+                let s = p.abs() + q.abs() + r.abs();
+                p /= s;
+                q /= s;
+                r /= s;
+                if m == l {
+                    break;
+                }
+                let u = a[m][m - 1].abs() * (q.abs() + r.abs());
+                let v = p.abs() * (a[m - 1][m - 1].abs() + z.abs() + a[m + 1][m + 1].abs());
+                if u + v == v {
+                    break;
+                }
+                m -= 1;
+            }
+            for i in (m + 2)..(nn + 1) {
+                a[i][i - 2] = 0.0;
+                if i != m + 2 {
+                    a[i][i - 3] = 0.0;
+                }
+            }
+
+            // Double QR step on rows l to nn and columns m to nn.
+            for k in m..nn {
+                if k != m {
+                    p = a[k][k - 1];
+                    q = a[k + 1][k - 1];
+                    r = if k != nn - 1 { a[k + 2][k - 1] } else { 0.0 };
+                    x = p.abs() + q.abs() + r.abs();
+                    if x != 0.0 {
+                        p /= x;
+                        q /= x;
+                        r /= x;
+                    }
+                }
+                let s = (p * p + q * q + r * r).sqrt().copysign(p);
+                if s == 0.0 {
+                    continue;
+                }
+                if k == m {
+                    if l != m {
+                        a[k][k - 1] = -a[k][k - 1];
+                    }
+                } else {
+                    a[k][k - 1] = -s * x;
+                }
+                p += s;
+                x = p / s;
+                y = q / s;
+                let z = r / s;
+                q /= p;
+                r /= p;
+                // Rows k and k + 1, and k + 2 unless k is the last but one.
+                let (upper, lower) = a.split_at_mut(k + 1);
+                let (row1, lower) = lower.split_first_mut().unwrap();
+                let mut row2 = if k != nn - 1 { lower.first_mut() } else { None };
+                for (j, (e0, e1)) in upper[k].iter_mut().zip(row1.iter_mut()).enumerate().take(nn + 1).skip(k) {
+                    let mut p = *e0 + q * *e1;
+                    if let Some(ref mut row2) = row2 {
+                        p += r * row2[j];
+                        row2[j] -= p * z;
+                    }
+                    *e1 -= p * y;
+                    *e0 -= p * x;
+                }
+                let mmin = if nn < k + 3 { nn } else { k + 3 };
+                for row in a.iter_mut().take(mmin + 1).skip(l) {
+                    let mut p = x * row[k] + y * row[k + 1];
+                    if k != nn - 1 {
+                        p += z * row[k + 2];
+                        row[k + 2] -= p * r;
+                    }
+                    row[k + 1] -= p * q;
+                    row[k] -= p;
+                }
+            }
+        }
+    }
+
+    values.remove(0);
+    Ok(values)
+}
+
+/// Whether the complex number is close enough to the real line to be printed
+/// as a real number.
+pub fn is_real(value: &Complex<f64>) -> bool {
+    value.im.abs() <= 1e-9 * value.re.abs().max(1.0)
+}
@@ -0,0 +1,152 @@
+use num_rational::Ratio;
+use num_traits::{Signed, Zero};
+
+use latex;
+use matrix::{Matrix, MatrixResult};
+use random::Rng;
+use reduce::EchelonForm;
+use ui::command::Command;
+
+/// Gives up on scrambling a matrix after this many rejected row operations.
+const MAX_ATTEMPTS: usize = 10_000;
+
+/// Controls what kind of exercises are generated.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct ExerciseOptions {
+    pub rows: usize,
+    pub cols: usize,
+    pub rank: usize,
+    /// The largest absolute value of an entry of the problem. The entries of
+    /// its RREF are at most half as large, so there is room to scramble it.
+    pub max_entry: i64,
+    /// How many random row operations are applied to the answer.
+    pub steps: usize
+}
+
+impl ExerciseOptions {
+
+    /// Options for full rank matrices of the given size with single digit
+    /// entries.
+    pub fn new(rows: usize, cols: usize) -> ExerciseOptions {
+        ExerciseOptions {
+            rows,
+            cols,
+            rank: rows.min(cols),
+            max_entry: 9,
+            steps: 3 * rows
+        }
+    }
+}
+
+/// A matrix to row reduce, along with its answer.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Exercise {
+    pub problem: Matrix<Ratio<i64>>,
+    /// The RREF of the problem.
+    pub answer: Matrix<Ratio<i64>>,
+    /// The (1-based) pivot columns of the answer.
+    pub pivots: Vec<usize>
+}
+
+/// Generates an exercise whose RREF has integer entries.
+///
+/// A random RREF with the requested rank is picked first, and random row
+/// operations with small coefficients are applied to it until it is no longer
+/// in RREF and has no zero rows left. Operations that would make an entry
+/// larger than `max_entry` are skipped, so the problem stays small too. Fails
+/// if that doesn't happen within `MAX_ATTEMPTS` operations.
+pub fn generate(options: &ExerciseOptions, rng: &mut Rng) -> MatrixResult<Exercise> {
+    let &ExerciseOptions { rows, cols, rank, max_entry, steps } = options;
+    if rows == 0 || cols == 0 {
+        return Err("the matrix must have at least one row and column".to_owned());
+    }
+    if rank > rows.min(cols) {
+        return Err(format!("a {}x{} matrix can't have rank {}", rows, cols, rank));
+    }
+    if max_entry < 1 {
+        return Err("the largest entry must be at least 1".to_owned());
+    }
+
+    let mut columns: Vec<usize> = (1..(cols + 1)).collect();
+    rng.shuffle(&mut columns);
+    let mut pivots = columns[..rank].to_vec();
+    pivots.sort();
+
+    let mut answer = Matrix::zero(rows, cols);
+    for (i, &p) in pivots.iter().enumerate() {
+        for j in p..(cols + 1) {
+            let value = if j == p {
+                1
+            } else if pivots.contains(&j) {
+                0
+            } else {
+                rng.range(-max_entry / 2, max_entry / 2)
+            };
+            answer.set(i + 1, j, Ratio::from_integer(value))?;
+        }
+    }
+
+    let scrambled = |problem: &Matrix<Ratio<i64>>| rank == 0 ||
+        (problem.echelon_form() != EchelonForm::Reduced
+         && problem.rows().iter().all(|row| row.iter().any(|x| !x.is_zero())));
+    let mut problem = answer.clone();
+    let mut applied = 0;
+    let mut attempts = 0;
+    while applied < steps || !scrambled(&problem) {
+        if attempts == MAX_ATTEMPTS {
+            return Err(format!("couldn't scramble a {}x{} matrix of rank {} in {} steps with entries up to {}",
+                               rows, cols, rank, steps, max_entry));
+        }
+        attempts += 1;
+        let candidate = random_command(rows, rng).apply(&problem)?;
+        if candidate.rows().iter().flatten().all(|x| x.abs() <= Ratio::from_integer(max_entry)) {
+            problem = candidate;
+            applied += 1;
+        }
+    }
+
+    Ok(Exercise {
+        problem,
+        answer,
+        pivots
+    })
+}
+
+/// Picks an invertible row operation with small integer coefficients.
+fn random_command(rows: usize, rng: &mut Rng) -> Command<Ratio<i64>> {
+    let last = rows as i64;
+    if rows == 1 {
+        return Command::ScaleRow { coeff: Ratio::from_integer(rng.nonzero_range(-2, 2)), row: 1 };
+    }
+    let row1 = rng.range(1, last) as usize;
+    let mut row2 = rng.range(1, last - 1) as usize;
+    if row2 >= row1 {
+        row2 += 1;
+    }
+    match rng.range(0, 5) {
+        0 => Command::SwapRow { row1, row2 },
+        1 => Command::ScaleRow { coeff: Ratio::from_integer(rng.nonzero_range(-2, 2)), row: row1 },
+        _ => Command::AddRow { coeff: Ratio::from_integer(rng.nonzero_range(-3, 3)), src: row1, dest: row2 }
+    }
+}
+
+/// Formats the exercises as a LaTeX document, with the problems followed by
+/// an answer key on a new page.
+pub fn worksheet(exercises: &[Exercise]) -> String {
+    let mut doc = String::from("\\documentclass{article}\n\\usepackage{amsmath}\n\\begin{document}\n\n");
+    doc.push_str("\\section*{Problems}\nFind the reduced row echelon form of each matrix.\n\n");
+    doc.push_str("\\begin{enumerate}\n");
+    for exercise in exercises {
+        doc.push_str(&format!("\\item $\n{}\n$\n", latex::matrix(&exercise.problem)));
+    }
+    doc.push_str("\\end{enumerate}\n\n\\newpage\n\\section*{Answers}\n\\begin{enumerate}\n");
+    for exercise in exercises {
+        let pivots: Vec<String> = exercise.pivots.iter().map(|p| p.to_string()).collect();
+        let pivots = if pivots.is_empty() { "none".to_owned() } else { pivots.join(", ") };
+        doc.push_str(&format!("\\item $\n{}\n$ \\\\\nRank {}, pivot columns: {}\n",
+                              latex::matrix(&exercise.answer), exercise.pivots.len(), pivots));
+    }
+    doc.push_str("\\end{enumerate}\n\n\\end{document}\n");
+    doc
+}
+
@@ -0,0 +1,177 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use latex;
+use matrix::{Field, Matrix, MatrixResult};
+
+/// The text formats a matrix can be exported as.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ExportFormat {
+    /// One row per line with entries separated by spaces, as the setup
+    /// screen accepts.
+    Plain,
+    /// `[1 2; 3 4]`
+    Matlab,
+    /// `[[1, 2], [3, 4]]`
+    Json,
+    /// A `bmatrix`.
+    Latex
+}
+
+impl ExportFormat {
+
+    pub fn all() -> Vec<ExportFormat> {
+        vec![ExportFormat::Plain, ExportFormat::Matlab, ExportFormat::Json, ExportFormat::Latex]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            ExportFormat::Plain => "plain",
+            ExportFormat::Matlab => "matlab",
+            ExportFormat::Json => "json",
+            ExportFormat::Latex => "latex"
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<ExportFormat, String> {
+        ExportFormat::all()
+            .into_iter()
+            .find(|format| format.name() == s)
+            .ok_or_else(|| format!("unknown export format: {}", s))
+    }
+}
+
+/// Writes the matrix in the given format. Fractions are written as `a/b` in
+/// every format except LaTeX, where they become `\frac`.
+pub fn export<T: Clone + Display>(matrix: &Matrix<T>, format: ExportFormat) -> String {
+    let rows: Vec<Vec<String>> = matrix.rows()
+        .iter()
+        .map(|row| row.iter().map(|x| x.to_string()).collect())
+        .collect();
+    match format {
+        ExportFormat::Plain => {
+            let lines: Vec<String> = rows.iter().map(|row| row.join(" ")).collect();
+            lines.join("\n")
+        },
+        ExportFormat::Matlab => {
+            let lines: Vec<String> = rows.iter().map(|row| row.join(" ")).collect();
+            format!("[{}]", lines.join("; "))
+        },
+        ExportFormat::Json => {
+            let lines: Vec<String> = rows.iter().map(|row| json_vector(row)).collect();
+            format!("[{}]", lines.join(", "))
+        },
+        ExportFormat::Latex => latex::matrix(matrix)
+    }
+}
+
+/// Writes the matrix followed by its pivot columns and bases of its four
+/// fundamental subspaces. The Matlab export assigns each of them to a
+/// variable, with the basis vectors as the columns of a matrix, and the JSON
+/// export is an object with the basis vectors as lists.
+pub fn export_with_subspaces<T: Field + Display>(matrix: &Matrix<T>, format: ExportFormat)
+    -> MatrixResult<String> {
+    let pivots: Vec<String> = matrix.pivot_columns()?.iter().map(|c| c.to_string()).collect();
+    // Each basis with the length of its vectors, which an empty basis can't
+    // tell.
+    let spaces = vec![
+        (Subspace::Column, matrix.rows, matrix.column_space()?),
+        (Subspace::Row, matrix.cols, matrix.row_space()?),
+        (Subspace::Null, matrix.cols, matrix.null_space()?),
+        (Subspace::LeftNull, matrix.rows, matrix.left_null_space()?)
+    ];
+    let text = |v: &Vec<T>| -> Vec<String> { v.iter().map(|x| x.to_string()).collect() };
+
+    let mut parts = Vec::new();
+    match format {
+        ExportFormat::Plain => {
+            parts.push(export(matrix, format));
+            parts.push(String::new());
+            parts.push(format!("pivot columns: {}", pivots.join(", ")));
+            for (space, _, basis) in spaces {
+                let vectors: Vec<String> = basis.iter().map(|v| format!("({})", text(v).join(", "))).collect();
+                let span = if vectors.is_empty() { "{0}".to_owned() } else { vectors.join(", ") };
+                parts.push(format!("{}: {}", space.name(), span));
+            }
+        },
+        ExportFormat::Matlab => {
+            parts.push(format!("A = {};", export(matrix, format)));
+            parts.push(format!("pivot_columns = [{}];", pivots.join(" ")));
+            for (space, n, basis) in spaces {
+                let value = if basis.is_empty() {
+                    format!("zeros({}, 0)", n)
+                } else {
+                    export(&Matrix::from_cols(basis), format)
+                };
+                parts.push(format!("{} = {};", space.name().replace(' ', "_"), value));
+            }
+        },
+        ExportFormat::Json => {
+            let mut fields = vec![format!("\"matrix\": {}", export(matrix, format)),
+                                  format!("\"pivot_columns\": [{}]", pivots.join(", "))];
+            for (space, _, basis) in spaces {
+                let vectors: Vec<String> = basis.iter().map(|v| json_vector(&text(v))).collect();
+                fields.push(format!("\"{}\": [{}]", space.name().replace(' ', "_"), vectors.join(", ")));
+            }
+            parts.push(format!("{{{}}}", fields.join(", ")));
+        },
+        ExportFormat::Latex => {
+            parts.push(format!("A = {}", export(matrix, format)));
+            parts.push(format!("\\text{{Pivot columns: }} {}", pivots.join(", ")));
+            for (space, _, basis) in spaces {
+                let vectors: Vec<String> = basis.into_iter()
+                    .map(|v| latex::matrix(&Matrix::from_cols(vec![v])))
+                    .collect();
+                let span = if vectors.is_empty() {
+                    "\\{\\mathbf{0}\\}".to_owned()
+                } else {
+                    format!("\\operatorname{{span}}\\left\\{{ {} \\right\\}}", vectors.join(", "))
+                };
+                parts.push(format!("{} = {}", space.latex(), span));
+            }
+        }
+    }
+    let separator = if format == ExportFormat::Latex { "\n\n" } else { "\n" };
+    Ok(parts.join(separator))
+}
+
+enum Subspace {
+    Column,
+    Row,
+    Null,
+    LeftNull
+}
+
+impl Subspace {
+
+    fn name(&self) -> &'static str {
+        match *self {
+            Subspace::Column => "column space",
+            Subspace::Row => "row space",
+            Subspace::Null => "null space",
+            Subspace::LeftNull => "left null space"
+        }
+    }
+
+    fn latex(&self) -> &'static str {
+        match *self {
+            Subspace::Column => "\\operatorname{Col}(A)",
+            Subspace::Row => "\\operatorname{Row}(A)",
+            Subspace::Null => "\\operatorname{Nul}(A)",
+            Subspace::LeftNull => "\\operatorname{Nul}(A^T)"
+        }
+    }
+}
+
+/// A JSON list of the numbers. JSON has no fractions, so they are written as
+/// strings.
+fn json_vector(entries: &[String]) -> String {
+    let entries: Vec<String> = entries.iter()
+        .map(|x| if x.contains('/') { format!("\"{}\"", x) } else { x.clone() })
+        .collect();
+    format!("[{}]", entries.join(", "))
+}
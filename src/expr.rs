@@ -0,0 +1,289 @@
+use num_rational::Ratio;
+use num_traits::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, One, Zero};
+use std::str::FromStr;
+
+/// A number type that arithmetic expressions can be evaluated in.
+pub trait Scalar: Sized {
+    /// Converts a literal made of digits and at most one decimal point, such
+    /// as `12`, `0.25` or `.5`.
+    fn from_decimal(text: &str) -> Result<Self, String>;
+
+    /// Applies `+`, `-`, `*`, `/` or `^`.
+    fn binary(op: char, a: Self, b: Self) -> Result<Self, String>;
+
+    fn negate(self) -> Result<Self, String>;
+
+    /// Applies a named function, such as `sqrt`.
+    fn function(name: &str, _arg: Self) -> Result<Self, String> {
+        Err(format!("unknown function {}", name))
+    }
+}
+
+/// Decimals are converted to exact fractions, and only integer powers are
+/// allowed so the result stays exact.
+impl Scalar for Ratio<i64> {
+
+    fn from_decimal(text: &str) -> Result<Self, String> {
+        let overflow = || format!("{} is too large", text);
+        let (whole, frac) = match text.find('.') {
+            Some(i) => (&text[..i], &text[(i + 1)..]),
+            None => (text, "")
+        };
+        let digits = format!("{}{}", whole, frac);
+        let numer = i64::from_str(&digits).map_err(|_| overflow())?;
+        let denom = (0..frac.len()).try_fold(1i64, |d, _| d.checked_mul(10))
+            .ok_or_else(overflow)?;
+        Ok(Ratio::new(numer, denom))
+    }
+
+    fn binary(op: char, a: Self, b: Self) -> Result<Self, String> {
+        let result = match op {
+            '+' => a.checked_add(&b),
+            '-' => a.checked_sub(&b),
+            '*' => a.checked_mul(&b),
+            '/' if b.is_zero() => return Err("division by zero".to_owned()),
+            '/' => a.checked_div(&b),
+            '^' => return ratio_pow(a, b),
+            _ => return Err(format!("unknown operator {}", op))
+        };
+        result.ok_or_else(|| "the result is too large".to_owned())
+    }
+
+    fn negate(self) -> Result<Self, String> {
+        self.numer().checked_neg()
+            .map(|numer| Ratio::new_raw(numer, *self.denom()))
+            .ok_or_else(|| "the result is too large".to_owned())
+    }
+}
+
+fn ratio_pow(base: Ratio<i64>, exp: Ratio<i64>) -> Result<Ratio<i64>, String> {
+    if !exp.is_integer() {
+        return Err("exponents must be integers".to_owned());
+    }
+    let overflow = || "the result is too large".to_owned();
+    let n = exp.to_integer();
+    if n < 0 && base.is_zero() {
+        return Err("division by zero".to_owned());
+    }
+    // The reciprocal negates a negative numerator, which overflows for the
+    // smallest one.
+    if n < 0 && base.numer().checked_neg().is_none() {
+        return Err(overflow());
+    }
+    let base = if n < 0 { base.recip() } else { base };
+
+    // Exponentiation by squaring
+    let mut n = n.checked_abs().ok_or_else(overflow)?;
+    let mut square = base;
+    let mut result = Ratio::one();
+    while n > 0 {
+        if n % 2 == 1 {
+            result = result.checked_mul(&square).ok_or_else(overflow)?;
+        }
+        n /= 2;
+        if n > 0 {
+            square = square.checked_mul(&square).ok_or_else(overflow)?;
+        }
+    }
+    Ok(result)
+}
+
+impl Scalar for f64 {
+
+    fn from_decimal(text: &str) -> Result<Self, String> {
+        f64::from_str(text).map_err(|_| format!("invalid number {}", text))
+    }
+
+    fn binary(op: char, a: Self, b: Self) -> Result<Self, String> {
+        match op {
+            '+' => Ok(a + b),
+            '-' => Ok(a - b),
+            '*' => Ok(a * b),
+            '/' if b == 0.0 => Err("division by zero".to_owned()),
+            '/' => Ok(a / b),
+            '^' => Ok(a.powf(b)),
+            _ => Err(format!("unknown operator {}", op))
+        }
+    }
+
+    fn negate(self) -> Result<Self, String> {
+        Ok(-self)
+    }
+
+    fn function(name: &str, arg: Self) -> Result<Self, String> {
+        match name {
+            "sqrt" if arg < 0.0 => Err("square root of a negative number".to_owned()),
+            "sqrt" => Ok(arg.sqrt()),
+            "abs" => Ok(arg.abs()),
+            _ => Err(format!("unknown function {}", name))
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Debug)]
+enum Token<'a> {
+    Number(&'a str),
+    Name(&'a str),
+    Symbol(char)
+}
+
+fn tokenize<'a>(text: &'a str) -> Result<Vec<Token<'a>>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+        if "+-*/^()".contains(c) {
+            tokens.push(Token::Symbol(c));
+            continue;
+        }
+
+        let is_number = c.is_ascii_digit() || c == '.';
+        if !is_number && !c.is_alphabetic() {
+            return Err(format!("unexpected '{}'", c));
+        }
+        let mut end = start + c.len_utf8();
+        while let Some(&(i, next)) = chars.peek() {
+            let continues = if is_number { next.is_ascii_digit() || next == '.' } else { next.is_alphanumeric() };
+            if !continues {
+                break;
+            }
+            end = i + next.len_utf8();
+            chars.next();
+        }
+        let word = &text[start..end];
+        if is_number {
+            if word == "." || word.matches('.').count() > 1 {
+                return Err(format!("invalid number {}", word));
+            }
+            tokens.push(Token::Number(word));
+        } else {
+            tokens.push(Token::Name(word));
+        }
+    }
+    Ok(tokens)
+}
+
+/// Evaluates an arithmetic expression such as `1/2 + 1/3`, `2^-3` or
+/// `3/-6`. The operators are `+`, `-`, `*`, `/` and `^`, which is right
+/// associative and binds tighter than negation, and parentheses can be used
+/// for grouping. Named functions are called like `sqrt(2)`.
+pub fn eval<T: Scalar>(text: &str) -> Result<T, String> {
+    let tokens = tokenize(text)?;
+    if tokens.is_empty() {
+        return Err("nothing to evaluate".to_owned());
+    }
+    let mut parser = Parser { tokens, pos: 0 };
+    let value = parser.sum()?;
+    match parser.peek() {
+        None => Ok(value),
+        Some(token) => Err(format!("unexpected {}", describe(token)))
+    }
+}
+
+fn describe(token: &Token) -> String {
+    match *token {
+        Token::Number(n) => n.to_owned(),
+        Token::Name(n) => n.to_owned(),
+        Token::Symbol(c) => format!("'{}'", c)
+    }
+}
+
+/// A recursive descent parser that evaluates as it goes.
+struct Parser<'a> {
+    tokens: Vec<Token<'a>>,
+    pos: usize
+}
+
+impl <'a> Parser<'a> {
+
+    fn peek(&self) -> Option<&Token<'a>> {
+        self.tokens.get(self.pos)
+    }
+
+    /// Consumes the next token if it is the symbol.
+    fn eat(&mut self, symbol: char) -> bool {
+        if self.peek() == Some(&Token::Symbol(symbol)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn next_symbol(&mut self, symbols: &str) -> Option<char> {
+        match self.peek() {
+            Some(&Token::Symbol(c)) if symbols.contains(c) => {
+                self.pos += 1;
+                Some(c)
+            },
+            _ => None
+        }
+    }
+
+    fn sum<T: Scalar>(&mut self) -> Result<T, String> {
+        let mut value = self.product()?;
+        while let Some(op) = self.next_symbol("+-") {
+            value = T::binary(op, value, self.product()?)?;
+        }
+        Ok(value)
+    }
+
+    fn product<T: Scalar>(&mut self) -> Result<T, String> {
+        let mut value = self.unary()?;
+        while let Some(op) = self.next_symbol("*/") {
+            value = T::binary(op, value, self.unary()?)?;
+        }
+        Ok(value)
+    }
+
+    fn unary<T: Scalar>(&mut self) -> Result<T, String> {
+        if self.eat('-') {
+            self.unary::<T>()?.negate()
+        } else if self.eat('+') {
+            self.unary()
+        } else {
+            self.power()
+        }
+    }
+
+    fn power<T: Scalar>(&mut self) -> Result<T, String> {
+        let base = self.atom()?;
+        if self.eat('^') {
+            T::binary('^', base, self.unary()?)
+        } else {
+            Ok(base)
+        }
+    }
+
+    fn atom<T: Scalar>(&mut self) -> Result<T, String> {
+        let token = self.peek().cloned().ok_or("unexpected end of input".to_owned())?;
+        self.pos += 1;
+        match token {
+            Token::Number(n) => T::from_decimal(n),
+            Token::Symbol('(') => {
+                let value = self.sum()?;
+                self.close()?;
+                Ok(value)
+            },
+            Token::Name(name) => {
+                if !self.eat('(') {
+                    return Err(format!("expected '(' after {}", name));
+                }
+                let arg = self.sum()?;
+                self.close()?;
+                T::function(name, arg)
+            },
+            token => Err(format!("unexpected {}", describe(&token)))
+        }
+    }
+
+    fn close(&mut self) -> Result<(), String> {
+        if self.eat(')') {
+            Ok(())
+        } else {
+            Err("missing ')'".to_owned())
+        }
+    }
+}
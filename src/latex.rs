@@ -0,0 +1,28 @@
+use std::fmt::Display;
+
+use matrix::Matrix;
+
+/// Formats the matrix as a LaTeX `bmatrix`, with fractions such as `-1/2`
+/// written as `-\frac{1}{2}`.
+pub fn matrix<T: Clone + Display>(matrix: &Matrix<T>) -> String {
+    let rows: Vec<String> = matrix.rows()
+        .iter()
+        .map(|row| {
+            let entries: Vec<String> = row.iter().map(|x| entry(&x.to_string())).collect();
+            entries.join(" & ")
+        })
+        .collect();
+    format!("\\begin{{bmatrix}}\n{}\n\\end{{bmatrix}}", rows.join(" \\\\\n"))
+}
+
+/// Formats one number, turning a fraction into `\frac`.
+pub fn entry(text: &str) -> String {
+    let (sign, text) = match text.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", text)
+    };
+    match text.find('/') {
+        Some(i) => format!("{}\\frac{{{}}}{{{}}}", sign, &text[..i], &text[(i + 1)..]),
+        None => format!("{}{}", sign, text)
+    }
+}
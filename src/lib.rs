@@ -1,4 +1,22 @@
 extern crate cursive;
+extern crate num_complex;
+extern crate num_rational;
+extern crate num_traits;
+extern crate unicode_width;
 
+pub mod config;
+pub mod eigen;
+pub mod exercise;
+pub mod export;
+pub mod expr;
+pub mod latex;
 pub mod matrix;
+pub mod orthogonal;
+pub mod parse;
+pub mod polynomial;
+pub mod quiz;
+pub mod random;
+pub mod reduce;
+pub mod session;
+pub mod storage;
 pub mod ui;
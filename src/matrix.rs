@@ -1,8 +1,21 @@
 use std::fmt;
-use std::ops::{Add, IndexMut, Sub};
+use std::ops::{Add, Div, Index, IndexMut, Mul, Neg, Sub};
+use std::slice;
+use num_traits::{FromPrimitive, One, Zero};
+
+use random::Rng;
 
 #[derive(PartialEq, Debug, Clone)]
 /// Matrix that contains the specified data.
+///
+/// Rows and columns are numbered from 1 in the methods that take them, as
+/// they are shown in the UI. These are checked: methods that read return
+/// `None` and methods that change the matrix return an error when a row or
+/// column isn't in the matrix, and leave it unchanged.
+///
+/// Indexing with `matrix[(row, col)]` counts from 0 instead, like slices,
+/// and panics when out of bounds. It is meant for library code that loops
+/// over the matrix, along with `iter` and `iter_mut`.
 pub struct Matrix<T> {
     data: Vec<T>,
     pub rows: usize,
@@ -11,6 +24,14 @@ pub struct Matrix<T> {
 
 pub type MatrixResult<T> = Result<T, String>;
 
+/// Number types that support the arithmetic needed for exact row reduction.
+pub trait Field: Clone + PartialEq + Zero + One + Sub<Self, Output = Self>
+    + Mul<Self, Output = Self> + Div<Self, Output = Self> + Neg<Output = Self> {}
+
+impl <T> Field for T
+    where T: Clone + PartialEq + Zero + One + Sub<T, Output = T>
+           + Mul<T, Output = T> + Div<T, Output = T> + Neg<Output = T> {}
+
 impl <T> Matrix<T> {
 
     /// Creates a matrix with the given size that contains the given data.
@@ -23,80 +44,332 @@ impl <T> Matrix<T> {
         }
     }
 
+    /// Creates a matrix whose columns are the given vectors. Each column must
+    /// have the same length.
+    pub fn from_cols(cols: Vec<Vec<T>>) -> Matrix<T> where T: Clone {
+        let ncols = cols.len();
+        let nrows = cols.first().map(|c| c.len()).unwrap_or(0);
+        assert!(cols.iter().all(|c| c.len() == nrows));
+        let mut data = Vec::with_capacity(nrows * ncols);
+        for i in 0..nrows {
+            for c in &cols {
+                data.push(c[i].clone());
+            }
+        }
+        Matrix::new(nrows, ncols, data)
+    }
+
     /// Creates a new matrix with the given size that contains the given value.
     pub fn filled(rows: usize, cols: usize, default: T) -> Matrix<T>
         where T: Clone {
         Matrix::new(rows, cols, vec![default; rows * cols])
     }
 
+    /// Creates the `n` by `n` identity matrix.
+    pub fn identity(n: usize) -> Matrix<T> where T: Clone + Zero + One {
+        let mut m = Matrix::filled(n, n, T::zero());
+        for i in 0..n {
+            m.data[i * n + i] = T::one();
+        }
+        m
+    }
+
+    /// Whether the matrix has as many rows as columns.
+    pub fn is_square(&self) -> bool {
+        self.rows == self.cols
+    }
+
+    /// Sum of the entries on the main diagonal.
+    pub fn trace(&self) -> T where T: Clone + Zero {
+        (0..self.rows.min(self.cols))
+            .fold(T::zero(), |acc, i| acc + self.data[i * self.cols + i].clone())
+    }
+
+    /// Whether the (1-based) row is in the matrix.
+    pub fn has_row(&self, row: usize) -> bool {
+        row > 0 && row <= self.rows
+    }
+
+    /// Whether the (1-based) column is in the matrix.
+    pub fn has_col(&self, col: usize) -> bool {
+        col > 0 && col <= self.cols
+    }
+
+    fn check_row(&self, row: usize) -> MatrixResult<()> {
+        if self.has_row(row) { Ok(()) } else { Err(format!("row {} is out of bounds", row)) }
+    }
+
     /// Gets the value in the specified row and column, if the row and column
     /// is contained in the matrix.
     pub fn get(&self, row: usize, col: usize) -> Option<T> where T: Clone {
-        if row > self.rows || col > self.cols || row < 1 || col < 1 {
+        if self.has_row(row) && self.has_col(col) {
+            Some(self[(row - 1, col - 1)].clone())
+        } else {
+            None
+        }
+    }
+
+    /// Gets a copy of the specified row.
+    pub fn get_row(&self, row: usize) -> Option<Vec<T>> where T: Clone {
+        self.row(row).map(|r| r.to_vec())
+    }
+
+    /// Gets a copy of the specified column.
+    pub fn get_col(&self, col: usize) -> Option<Vec<T>> where T: Clone {
+        self.col_iter(col).map(|c| c.cloned().collect())
+    }
+
+    /// Borrows the specified row.
+    pub fn row(&self, row: usize) -> Option<&[T]> {
+        if self.has_row(row) {
+            let start = (row - 1) * self.cols;
+            Some(&self.data[start..(start + self.cols)])
+        } else {
             None
+        }
+    }
+
+    /// Mutably borrows the specified row.
+    pub fn row_mut(&mut self, row: usize) -> Option<&mut [T]> {
+        if self.has_row(row) {
+            let start = (row - 1) * self.cols;
+            Some(&mut self.data[start..(start + self.cols)])
         } else {
-            Some(self.data[(row - 1) * self.cols + (col - 1)].clone())
+            None
         }
     }
 
-    pub fn get_row<'a>(&self, row: usize) -> Vec<T> where T: Clone {
-        let start = (row - 1) * self.cols;
-        self.data[start..(start + self.cols)].to_owned()
+    /// Iterates over the entries of the specified column, from top to
+    /// bottom.
+    pub fn col_iter(&self, col: usize) -> Option<impl Iterator<Item = &T>> {
+        if self.has_col(col) {
+            // A matrix with no rows has no entries to step through.
+            let start = (col - 1).min(self.data.len());
+            Some(self.data[start..].iter().step_by(self.cols))
+        } else {
+            None
+        }
+    }
+
+    /// Iterates over the entries row by row.
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.data.iter()
+    }
+
+    /// Iterates mutably over the entries row by row.
+    pub fn iter_mut(&mut self) -> slice::IterMut<'_, T> {
+        self.data.iter_mut()
     }
 
     pub fn set(&mut self, row: usize, col: usize, value: T) -> MatrixResult<()> {
-        if row <= self.rows && col <= self.cols && row > 0 && col > 0 {
-            self.data[(row - 1) * self.cols + (col - 1)] = value;
+        if self.has_row(row) && self.has_col(col) {
+            self[(row - 1, col - 1)] = value;
             Ok(())
         } else {
-            Err(format!("{}, {} is out of bounds", row, col))
+            Err(format!("({}, {}) is out of bounds", row, col))
         }
     }
 
     pub fn set_row(&mut self, row: usize, values: &[T]) -> MatrixResult<()>
         where T: Clone {
-        let mut errs = Vec::<String>::new();
-        if row > self.rows || row <= 0 {
-            errs.push(format!("row {} out of bounds", row));
-        }
+        self.check_row(row)?;
         if values.len() != self.cols {
-            errs.push(format!("value length does not match row length ({} != {})",
-                              values.len(), self.cols));
-        }
-        if errs.len() == 0 {
-            let start = (row - 1) * self.cols;
-            let slice = self.data.index_mut(start..(start + self.cols));
-            for i in 0..slice.len() {
-                slice[i] = values[i].clone();
-            }
-            Ok(())
-        } else {
-            Err("rip".to_owned())
+            return Err(format!("row {} has {} entries, not {}", row, self.cols, values.len()));
         }
+        self.row_mut(row).unwrap().clone_from_slice(values);
+        Ok(())
+    }
+
+    /// Creates the transpose of the matrix.
+    pub fn transpose(&self) -> Matrix<T> where T: Clone {
+        let data = (1..(self.cols + 1))
+            .flat_map(|c| self.col_iter(c).unwrap().cloned())
+            .collect();
+        Matrix::new(self.cols, self.rows, data)
+    }
+
+    /// Creates a new matrix by applying the function to every element.
+    pub fn map<U, F>(&self, f: F) -> Matrix<U> where F: Fn(&T) -> U {
+        Matrix::new(self.rows, self.cols, self.data.iter().map(f).collect())
     }
 
     /// Changes each element of the row in-place with the given function
     pub fn row_foreach<F>(&mut self, row: usize, cb: F) -> MatrixResult<()>
         where F: Fn(&T) -> T
     {
-        if row <= self.rows && row > 0 {
-            let start = (row - 1) * self.cols;
-            let slice = self.data.index_mut(start..(start + self.cols));
-            for i in 0..slice.len() {
-                slice[i] = cb(&slice[i]);
-            }
-            Ok(())
-        } else {
-            Err(format!("row {} is not in bounds", row))
+        self.check_row(row)?;
+        for x in self.row_mut(row).unwrap() {
+            *x = cb(x);
         }
+        Ok(())
     }
 
-    pub fn rows(&self) -> Vec<Vec<T>> where T: Clone {
-        self.data
-            .chunks(self.cols)
-            .map(|sl| sl.to_vec())
+    /// Interchanges two rows in place.
+    pub fn swap_rows(&mut self, row1: usize, row2: usize) -> MatrixResult<()> {
+        self.check_row(row1)?;
+        self.check_row(row2)?;
+        for c in 0..self.cols {
+            self.data.swap((row1 - 1) * self.cols + c, (row2 - 1) * self.cols + c);
+        }
+        Ok(())
+    }
+
+    /// Adds `coeff` times the `src` row to the `dest` row in place.
+    pub fn add_scaled_row(&mut self, src: usize, coeff: &T, dest: usize) -> MatrixResult<()>
+        where T: Clone + Add<T, Output = T> + Mul<T, Output = T> {
+        self.check_row(src)?;
+        self.check_row(dest)?;
+        for c in 0..self.cols {
+            let s = self[(src - 1, c)].clone();
+            let d = &mut self[(dest - 1, c)];
+            *d = coeff.clone() * s + d.clone();
+        }
+        Ok(())
+    }
+
+    /// Gets the (1-based) positions of the cells of `other` that differ from
+    /// this matrix. Every cell differs if the sizes don't match.
+    pub fn diff(&self, other: &Matrix<T>) -> Vec<(usize, usize)> where T: PartialEq {
+        let same_size = self.rows == other.rows && self.cols == other.cols;
+        (0..other.data.len())
+            .filter(|&i| !same_size || self.data[i] != other.data[i])
+            .map(|i| (i / other.cols + 1, i % other.cols + 1))
             .collect()
     }
+
+    pub fn rows(&self) -> Vec<Vec<T>> where T: Clone {
+        (1..(self.rows + 1)).map(|r| self.get_row(r).unwrap()).collect()
+    }
+
+    pub fn cols(&self) -> Vec<Vec<T>> where T: Clone {
+        (1..(self.cols + 1)).map(|c| self.get_col(c).unwrap()).collect()
+    }
+}
+
+/// Constructors for common kinds of matrices.
+impl <T: Clone + Zero + One> Matrix<T> {
+
+    /// Creates a matrix of the given size filled with zeros.
+    pub fn zero(rows: usize, cols: usize) -> Matrix<T> {
+        Matrix::filled(rows, cols, T::zero())
+    }
+
+    /// Creates a square matrix with the given values on the diagonal.
+    pub fn diagonal(values: &[T]) -> Matrix<T> {
+        let n = values.len();
+        let mut m = Matrix::zero(n, n);
+        for (i, v) in values.iter().enumerate() {
+            m.data[i * n + i] = v.clone();
+        }
+        m
+    }
+
+    /// Creates an `n` by `n` matrix with `diag` on the diagonal, `lower` just
+    /// below it and `upper` just above it.
+    pub fn tridiagonal(n: usize, lower: T, diag: T, upper: T) -> Matrix<T> {
+        let mut m = Matrix::zero(n, n);
+        for i in 0..n {
+            m.data[i * n + i] = diag.clone();
+            if i > 0 {
+                m.data[i * n + i - 1] = lower.clone();
+            }
+            if i + 1 < n {
+                m.data[i * n + i + 1] = upper.clone();
+            }
+        }
+        m
+    }
+
+    /// Creates the square Vandermonde matrix whose rows are the powers
+    /// `1, x, x^2, ...` of each of the given values.
+    pub fn vandermonde(values: &[T]) -> Matrix<T> {
+        let n = values.len();
+        let mut data = Vec::with_capacity(n * n);
+        for x in values {
+            let mut power = T::one();
+            for _ in 0..n {
+                data.push(power.clone());
+                power = power * x.clone();
+            }
+        }
+        Matrix::new(n, n, data)
+    }
+
+    /// Creates the `n` by `n` Hilbert matrix, whose entries are `1/(i+j-1)`.
+    pub fn hilbert(n: usize) -> Matrix<T> where T: Div<T, Output = T> + FromPrimitive {
+        let mut data = Vec::with_capacity(n * n);
+        for i in 1..(n + 1) {
+            for j in 1..(n + 1) {
+                let denom = T::from_usize(i + j - 1).expect("denominator out of range");
+                data.push(T::one() / denom);
+            }
+        }
+        Matrix::new(n, n, data)
+    }
+
+    /// Creates a matrix of random integers between `min` and `max`, inclusive.
+    pub fn random_integers(rows: usize, cols: usize, min: i64, max: i64, rng: &mut Rng) -> Matrix<T>
+        where T: FromPrimitive {
+        let data = (0..(rows * cols))
+            .map(|_| T::from_i64(rng.range(min, max)).expect("entry out of range"))
+            .collect();
+        Matrix::new(rows, cols, data)
+    }
+
+    /// Creates a random integer matrix with exactly the given rank. It is the
+    /// product of two full rank factors with entries of absolute value at most
+    /// `max_entry`, with its rows and columns shuffled.
+    pub fn random_with_rank(rows: usize, cols: usize, rank: usize, max_entry: i64, rng: &mut Rng)
+        -> MatrixResult<Matrix<T>> where T: FromPrimitive {
+        if rank > rows.min(cols) {
+            return Err(format!("a {}x{} matrix can't have rank {}", rows, cols, rank));
+        }
+        // The top of the left factor and the left of the right factor are
+        // unit triangular, so both factors have full rank.
+        let left = Matrix::<T>::random_integers(rows, rank, -max_entry, max_entry, rng)
+            .unit_triangular(true);
+        let right = Matrix::<T>::random_integers(rank, cols, -max_entry, max_entry, rng)
+            .unit_triangular(false);
+        Ok((&left * &right).shuffled(rng))
+    }
+
+    /// Creates a random `n` by `n` integer matrix with determinant 1 or -1, so
+    /// its inverse also has integer entries. It is the product of unit
+    /// triangular matrices with entries of absolute value at most `max_entry`,
+    /// with its rows shuffled.
+    pub fn random_with_integer_inverse(n: usize, max_entry: i64, rng: &mut Rng) -> Matrix<T>
+        where T: FromPrimitive {
+        let lower = Matrix::<T>::random_integers(n, n, -max_entry, max_entry, rng)
+            .unit_triangular(true);
+        let upper = Matrix::<T>::random_integers(n, n, -max_entry, max_entry, rng)
+            .unit_triangular(false);
+        let mut rows = (&lower * &upper).rows();
+        rng.shuffle(&mut rows);
+        Matrix::from_cols(rows).transpose()
+    }
+
+    /// Puts ones on the diagonal and zeros on one side of it.
+    fn unit_triangular(mut self, lower: bool) -> Matrix<T> {
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                if i == j {
+                    self.data[i * self.cols + j] = T::one();
+                } else if (j > i) == lower {
+                    self.data[i * self.cols + j] = T::zero();
+                }
+            }
+        }
+        self
+    }
+
+    /// Shuffles the rows and then the columns.
+    fn shuffled(&self, rng: &mut Rng) -> Matrix<T> {
+        let mut rows = self.rows();
+        rng.shuffle(&mut rows);
+        let mut cols = Matrix::from_cols(rows).transpose().cols();
+        rng.shuffle(&mut cols);
+        Matrix::from_cols(cols)
+    }
 }
 
 impl <'a, T: Add<T, Output = T> + Clone> Add for &'a Matrix<T> {
@@ -119,12 +392,48 @@ impl <'a, T: Sub<T, Output = T> + Clone> Sub for &'a Matrix<T> {
     }
 }
 
-impl <T: Clone + fmt::Display> fmt::Display for Matrix<T> {
+impl <T: Zero + Mul<T, Output = T> + Clone> Mul for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul(self, rhs: &Matrix<T>) -> Self::Output {
+        assert!(self.cols == rhs.rows);
+        let mut new_data = Vec::with_capacity(self.rows * rhs.cols);
+        for i in 0..self.rows {
+            for j in 0..rhs.cols {
+                let v = (0..self.cols).fold(T::zero(), |acc, k| {
+                    acc + self.data[i * self.cols + k].clone() * rhs.data[k * rhs.cols + j].clone()
+                });
+                new_data.push(v);
+            }
+        }
+        Matrix::new(self.rows, rhs.cols, new_data)
+    }
+}
+
+/// Indexes by `(row, col)`, counting from 0 like slices do. Panics if the
+/// position isn't in the matrix.
+impl <T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        assert!(row < self.rows && col < self.cols, "({}, {}) is out of bounds", row, col);
+        &self.data[row * self.cols + col]
+    }
+}
+
+impl <T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        assert!(row < self.rows && col < self.cols, "({}, {}) is out of bounds", row, col);
+        &mut self.data[row * self.cols + col]
+    }
+}
+
+impl <T: fmt::Display> fmt::Display for Matrix<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let mut buf = String::new();
         for i in 1..(self.rows + 1) {
-            for j in 1..(self.cols + 1) {
-                buf.push_str(&format!("{}", self.get(i, j).unwrap()));
+            for x in self.row(i).unwrap() {
+                buf.push_str(&format!("{}", x));
             }
             if i != self.rows {
                 buf.push_str("\n");
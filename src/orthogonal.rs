@@ -0,0 +1,135 @@
+use std::fmt;
+use std::ops::{Add, Div, Mul, Sub};
+use num_traits::Zero;
+
+use matrix::{Matrix, MatrixResult};
+
+/// A single projection step of the Gram-Schmidt process, which replaces
+/// column `dest` with `dest - coeff * src`.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Projection<T> {
+    pub coeff: T,
+    pub src: usize,
+    pub dest: usize
+}
+
+impl <T> Projection<T> {
+    /// Applies the projection to the columns of the given matrix.
+    pub fn apply(&self, matrix: &Matrix<T>) -> MatrixResult<Matrix<T>>
+        where T: Clone + Sub<T, Output = T> + Mul<T, Output = T> {
+        if self.src > matrix.cols || self.dest > matrix.cols || self.src < 1 || self.dest < 1 {
+            return Err(format!("column {} or {} is out of bounds", self.src, self.dest));
+        }
+        let mut cols = matrix.cols();
+        let new_dest = subtract_multiple(&cols[self.dest - 1], &self.coeff, &cols[self.src - 1]);
+        cols[self.dest - 1] = new_dest;
+        Ok(Matrix::from_cols(cols))
+    }
+}
+
+impl <T: fmt::Display> fmt::Display for Projection<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "u{} = u{} - ({}) u{}", self.dest, self.dest, self.coeff, self.src)
+    }
+}
+
+fn dot<T>(a: &[T], b: &[T]) -> T
+    where T: Clone + Zero + Add<T, Output = T> + Mul<T, Output = T> {
+    a.iter()
+        .zip(b)
+        .fold(T::zero(), |acc, (x, y)| acc + x.clone() * y.clone())
+}
+
+fn subtract_multiple<T>(v: &[T], coeff: &T, u: &[T]) -> Vec<T>
+    where T: Clone + Sub<T, Output = T> + Mul<T, Output = T> {
+    v.iter()
+        .zip(u)
+        .map(|(x, y)| x.clone() - coeff.clone() * y.clone())
+        .collect()
+}
+
+fn norm(v: &[f64]) -> f64 {
+    dot(v, v).sqrt()
+}
+
+/// Relative tolerance below which a column is considered to be a linear
+/// combination of the previous columns.
+const DEPENDENCE_TOLERANCE: f64 = 1e-10;
+
+impl <T> Matrix<T>
+    where T: Clone + PartialEq + Zero + Add<T, Output = T> + Sub<T, Output = T>
+           + Mul<T, Output = T> + Div<T, Output = T> {
+
+    /// Runs the Gram-Schmidt process on the columns of the matrix without
+    /// normalizing, so the computation stays exact for rational matrices.
+    /// Returns a matrix with mutually orthogonal columns together with the
+    /// projections that were performed, in order.
+    pub fn orthogonalize(&self) -> MatrixResult<(Matrix<T>, Vec<Projection<T>>)> {
+        let mut basis = self.cols();
+        let mut steps = Vec::new();
+        for j in 0..basis.len() {
+            for i in 0..j {
+                let coeff = dot(&basis[j], &basis[i]) / dot(&basis[i], &basis[i]);
+                if coeff.is_zero() {
+                    continue;
+                }
+                basis[j] = subtract_multiple(&basis[j], &coeff, &basis[i]);
+                steps.push(Projection { coeff, src: i + 1, dest: j + 1 });
+            }
+            if basis[j].iter().all(|x| x.is_zero()) {
+                return Err(format!("column {} is linearly dependent on the previous columns", j + 1));
+            }
+        }
+        Ok((Matrix::from_cols(basis), steps))
+    }
+}
+
+impl Matrix<f64> {
+
+    /// Computes an orthonormal basis of the column space using classical
+    /// Gram-Schmidt, where each column is projected against the original
+    /// column vector.
+    pub fn gram_schmidt(&self) -> MatrixResult<Matrix<f64>> {
+        self.gram_schmidt_qr(false).map(|(q, _)| q)
+    }
+
+    /// Computes an orthonormal basis of the column space using modified
+    /// Gram-Schmidt, which is more stable than the classical variant.
+    pub fn modified_gram_schmidt(&self) -> MatrixResult<Matrix<f64>> {
+        self.gram_schmidt_qr(true).map(|(q, _)| q)
+    }
+
+    /// Computes the thin QR decomposition of the matrix, where `Q` has
+    /// orthonormal columns and `R` is upper triangular. The matrix must have
+    /// linearly independent columns.
+    pub fn qr(&self) -> MatrixResult<(Matrix<f64>, Matrix<f64>)> {
+        self.gram_schmidt_qr(true)
+    }
+
+    fn gram_schmidt_qr(&self, modified: bool) -> MatrixResult<(Matrix<f64>, Matrix<f64>)> {
+        if self.cols > self.rows {
+            return Err(format!("{} columns cannot be independent in {} dimensions",
+                               self.cols, self.rows));
+        }
+        let cols = self.cols();
+        let mut q: Vec<Vec<f64>> = Vec::with_capacity(self.cols);
+        let mut r = Matrix::filled(self.cols, self.cols, 0.0);
+        for (j, col) in cols.iter().enumerate() {
+            let mut v = col.clone();
+            for (i, qi) in q.iter().enumerate() {
+                // The classical variant projects the original column, whereas
+                // the modified one projects what is left of it so far.
+                let coeff = if modified { dot(qi, &v) } else { dot(qi, col) };
+                v = subtract_multiple(&v, &coeff, qi);
+                r.set(i + 1, j + 1, coeff)?;
+            }
+            let len = norm(&v);
+            if len <= DEPENDENCE_TOLERANCE * norm(col).max(1.0) {
+                return Err(format!("column {} is linearly dependent on the previous columns", j + 1));
+            }
+            r.set(j + 1, j + 1, len)?;
+            q.push(v.iter().map(|x| x / len).collect());
+        }
+        Ok((Matrix::from_cols(q), r))
+    }
+}
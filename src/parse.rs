@@ -0,0 +1,87 @@
+use std::str::FromStr;
+
+use matrix::{Matrix, MatrixResult};
+
+/// Parses a matrix from text, using `FromStr` for each entry.
+///
+/// See `parse_matrix_with` for the accepted formats.
+pub fn parse_matrix<T: FromStr>(text: &str) -> MatrixResult<Matrix<T>> {
+    parse_matrix_with(text, |entry| T::from_str(entry).map_err(|_| "not a number".to_owned()))
+}
+
+/// Parses a matrix from text, inferring its size. The accepted formats are:
+///
+/// * rows on separate lines or separated by semicolons, with entries
+///   separated by whitespace or commas
+/// * MATLAB syntax, such as `[1 2; 3 4]`
+/// * JSON arrays, such as `[[1, 2], [3, 4]]`, where entries may be quoted
+///
+/// Every entry is parsed with `parse_entry`, and all rows must have the same
+/// length.
+pub fn parse_matrix_with<T, F>(text: &str, parse_entry: F) -> MatrixResult<Matrix<T>>
+    where F: Fn(&str) -> Result<T, String> {
+    let rows: Vec<Vec<&str>> = split_rows(text)?
+        .into_iter()
+        .map(split_entries)
+        .filter(|row| !row.is_empty())
+        .collect();
+
+    if rows.is_empty() {
+        return Err("the matrix is empty".to_owned());
+    }
+    let cols = rows[0].len();
+    let mut data = Vec::with_capacity(rows.len() * cols);
+    for (i, row) in rows.iter().enumerate() {
+        if row.len() != cols {
+            return Err(format!("row {} has {} entries, but row 1 has {}", i + 1, row.len(), cols));
+        }
+        for (j, entry) in row.iter().enumerate() {
+            let value = parse_entry(entry)
+                .map_err(|e| format!("entry ({}, {}) \"{}\": {}", i + 1, j + 1, entry, e))?;
+            data.push(value);
+        }
+    }
+    Ok(Matrix::new(rows.len(), cols, data))
+}
+
+fn split_rows(text: &str) -> MatrixResult<Vec<&str>> {
+    let text = text.trim();
+    if !(text.starts_with('[') && text.ends_with(']')) {
+        return Ok(text.split([';', '\n']).collect());
+    }
+
+    let inner = text[1..(text.len() - 1)].trim();
+    if !inner.starts_with('[') {
+        // MATLAB syntax
+        return Ok(inner.split([';', '\n']).collect());
+    }
+
+    // JSON arrays: each row is enclosed in brackets, and the rows are
+    // separated by commas.
+    let mut rows = Vec::new();
+    let mut start = None;
+    for (i, c) in inner.char_indices() {
+        match (c, start) {
+            ('[', None) => start = Some(i + 1),
+            (']', Some(s)) => {
+                rows.push(&inner[s..i]);
+                start = None;
+            },
+            ('[', Some(_)) | (']', None) => return Err("unbalanced brackets".to_owned()),
+            (c, None) if c != ',' && !c.is_whitespace() =>
+                return Err(format!("unexpected '{}' between rows", c)),
+            _ => ()
+        }
+    }
+    if start.is_some() {
+        return Err("unbalanced brackets".to_owned());
+    }
+    Ok(rows)
+}
+
+fn split_entries(row: &str) -> Vec<&str> {
+    row.split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| entry.trim_matches('"'))
+        .collect()
+}
@@ -0,0 +1,328 @@
+use std::fmt;
+use std::ops::{Add, Mul, Sub};
+use num_rational::Ratio;
+use num_traits::{CheckedAdd, CheckedMul, One, Signed, Zero};
+
+/// Primes up to this are found by trial division when factoring. Anything
+/// left over after that is below `TRIAL_LIMIT^3`, so it has at most two prime
+/// factors.
+const TRIAL_LIMIT: i64 = 1 << 21;
+
+/// The most candidates that `rational_roots` will try.
+const MAX_CANDIDATES: usize = 1_000_000;
+
+/// A polynomial in a single variable, stored as its coefficients in order of
+/// increasing degree.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Polynomial<T> {
+    coeffs: Vec<T>
+}
+
+impl <T: Zero> Polynomial<T> {
+
+    /// Creates a polynomial from its coefficients, starting with the constant
+    /// term. Trailing zero coefficients are removed.
+    pub fn new(mut coeffs: Vec<T>) -> Polynomial<T> {
+        while coeffs.last().map(|c| c.is_zero()).unwrap_or(false) {
+            coeffs.pop();
+        }
+        Polynomial { coeffs }
+    }
+
+    /// The coefficients in order of increasing degree.
+    pub fn coeffs(&self) -> &[T] {
+        &self.coeffs
+    }
+
+    /// The degree of the polynomial, or `None` for the zero polynomial.
+    pub fn degree(&self) -> Option<usize> {
+        if self.coeffs.is_empty() {
+            None
+        } else {
+            Some(self.coeffs.len() - 1)
+        }
+    }
+
+    /// Evaluates the polynomial at the given value using Horner's method.
+    pub fn eval(&self, x: &T) -> T
+        where T: Clone + Add<T, Output = T> + Mul<T, Output = T> {
+        self.coeffs.iter().rev().fold(T::zero(), |acc, c| acc * x.clone() + c.clone())
+    }
+
+    /// Divides the polynomial by `x - root`, returning the quotient and the
+    /// remainder.
+    pub fn divide_linear(&self, root: &T) -> (Polynomial<T>, T)
+        where T: Clone + Add<T, Output = T> + Mul<T, Output = T> {
+        let mut quotient = Vec::with_capacity(self.coeffs.len());
+        let mut carry = T::zero();
+        for c in self.coeffs.iter().rev() {
+            carry = carry * root.clone() + c.clone();
+            quotient.push(carry.clone());
+        }
+        let remainder = quotient.pop().unwrap_or_else(T::zero);
+        quotient.reverse();
+        (Polynomial::new(quotient), remainder)
+    }
+}
+
+impl Polynomial<Ratio<i64>> {
+
+    /// Finds every rational root along with its multiplicity, using the
+    /// rational root theorem. Fails if the coefficients are too large to check
+    /// every candidate exactly.
+    pub fn rational_roots(&self) -> Result<Vec<(Ratio<i64>, usize)>, String> {
+        let mut roots = Vec::new();
+        let mut poly = self.clone();
+
+        // Factor out zero roots first, since the rational root theorem needs a
+        // nonzero constant term.
+        let mut zero_mult = 0;
+        while poly.coeffs.len() > 1 && poly.coeffs[0].is_zero() {
+            poly.coeffs.remove(0);
+            zero_mult += 1;
+        }
+        if zero_mult > 0 {
+            roots.push((Ratio::zero(), zero_mult));
+        }
+        if poly.degree().unwrap_or(0) == 0 {
+            return Ok(roots);
+        }
+
+        // Clear the denominators so the coefficients are integers.
+        let overflow = || "the coefficients are too large to find the rational roots".to_owned();
+        let lcm = poly.coeffs.iter().try_fold(1, |acc, c| lcm(acc, *c.denom())).ok_or_else(overflow)?;
+        let constant = (*poly.coeffs[0].numer()).checked_mul(lcm / poly.coeffs[0].denom()).ok_or_else(overflow)?;
+        let last = &poly.coeffs[poly.coeffs.len() - 1];
+        let leading = (*last.numer()).checked_mul(lcm / last.denom()).ok_or_else(overflow)?;
+
+        let numers = divisors(constant)?;
+        let denoms = divisors(leading)?;
+        if numers.len() * denoms.len() > MAX_CANDIDATES {
+            return Err(format!("{} and {} have too many divisors to find the rational roots", constant, leading));
+        }
+        for &p in &numers {
+            for &q in &denoms {
+                for candidate in &[Ratio::new(p, q), Ratio::new(-p, q)] {
+                    if roots.iter().any(|(r, _)| r == candidate) {
+                        continue;
+                    }
+                    let mut mult = 0;
+                    while poly.degree().unwrap_or(0) > 0 && poly.may_have_root(candidate) {
+                        let (quotient, rem) = poly.checked_divide_linear(candidate).ok_or_else(overflow)?;
+                        if !rem.is_zero() {
+                            break;
+                        }
+                        poly = quotient;
+                        mult += 1;
+                    }
+                    if mult > 0 {
+                        roots.push((*candidate, mult));
+                    }
+                }
+            }
+        }
+        Ok(roots)
+    }
+
+    /// Whether the value could be a root, judging by the polynomial's value in
+    /// floating point. Exact roots are never ruled out, since the rounding
+    /// error is far smaller than the threshold.
+    fn may_have_root(&self, x: &Ratio<i64>) -> bool {
+        let x = *x.numer() as f64 / *x.denom() as f64;
+        let (value, size) = self.coeffs.iter().rev().fold((0.0, 0.0), |(value, size), c| {
+            let c = *c.numer() as f64 / *c.denom() as f64;
+            (value * x + c, size * x.abs() + c.abs())
+        });
+        value.abs() <= 1e-9 * size
+    }
+
+    /// `divide_linear`, or `None` if the arithmetic overflows.
+    fn checked_divide_linear(&self, root: &Ratio<i64>) -> Option<(Polynomial<Ratio<i64>>, Ratio<i64>)> {
+        let mut quotient = Vec::with_capacity(self.coeffs.len());
+        let mut carry = Ratio::zero();
+        for c in self.coeffs.iter().rev() {
+            carry = carry.checked_mul(root)?.checked_add(c)?;
+            quotient.push(carry);
+        }
+        let remainder = quotient.pop().unwrap_or_else(Ratio::zero);
+        quotient.reverse();
+        Some((Polynomial::new(quotient), remainder))
+    }
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 { a.abs() } else { gcd(b, a % b) }
+}
+
+/// The least common multiple of two positive numbers, or `None` if it
+/// overflows.
+fn lcm(a: i64, b: i64) -> Option<i64> {
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// The positive divisors of `n`, which must not be zero.
+fn divisors(n: i64) -> Result<Vec<i64>, String> {
+    let mut rest = n.checked_abs().ok_or_else(|| format!("{} is too large to factor", n))?;
+    let mut factors = Vec::new();
+    let mut d = 2;
+    while d <= TRIAL_LIMIT && d <= rest / d {
+        let mut power = 0;
+        while rest % d == 0 {
+            rest /= d;
+            power += 1;
+        }
+        if power > 0 {
+            factors.push((d, power));
+        }
+        d += 1;
+    }
+    if rest > 1 {
+        // If trial division stopped early, whatever is left has at most two
+        // prime factors, both larger than the limit.
+        if d <= rest / d && !is_prime(rest as u64) {
+            return Err(format!("{} has prime factors too large to find", n));
+        }
+        factors.push((rest, 1));
+    }
+
+    let mut divisors = vec![1];
+    for (p, power) in factors {
+        let mut multiples = Vec::with_capacity(divisors.len() * power);
+        let mut scale = p;
+        for _ in 0..power {
+            multiples.extend(divisors.iter().map(|d| d * scale));
+            scale = scale.saturating_mul(p);
+        }
+        divisors.extend(multiples);
+    }
+    Ok(divisors)
+}
+
+/// A Miller-Rabin test, which is exact for every `u64` with these bases.
+fn is_prime(n: u64) -> bool {
+    const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    if n < 2 {
+        return false;
+    }
+    for &p in &BASES {
+        if n.is_multiple_of(p) {
+            return n == p;
+        }
+    }
+    let mul_mod = |a: u64, b: u64| (a as u128 * b as u128 % n as u128) as u64;
+    let pow_mod = |mut base: u64, mut exp: u64| {
+        let mut result = 1;
+        while exp > 0 {
+            if exp % 2 == 1 {
+                result = mul_mod(result, base);
+            }
+            base = mul_mod(base, base);
+            exp /= 2;
+        }
+        result
+    };
+    let zeros = (n - 1).trailing_zeros();
+    let odd = (n - 1) >> zeros;
+    BASES.iter().all(|&a| {
+        let mut x = pow_mod(a, odd);
+        if x == 1 || x == n - 1 {
+            return true;
+        }
+        for _ in 1..zeros {
+            x = mul_mod(x, x);
+            if x == n - 1 {
+                return true;
+            }
+        }
+        false
+    })
+}
+
+impl <T: Zero + Clone + Add<T, Output = T>> Add for &Polynomial<T> {
+    type Output = Polynomial<T>;
+
+    fn add(self, rhs: &Polynomial<T>) -> Self::Output {
+        let len = self.coeffs.len().max(rhs.coeffs.len());
+        let coeffs = (0..len)
+            .map(|i| {
+                let a = self.coeffs.get(i).cloned().unwrap_or_else(T::zero);
+                let b = rhs.coeffs.get(i).cloned().unwrap_or_else(T::zero);
+                a + b
+            })
+            .collect();
+        Polynomial::new(coeffs)
+    }
+}
+
+impl <T: Zero + Clone + Sub<T, Output = T>> Sub for &Polynomial<T> {
+    type Output = Polynomial<T>;
+
+    fn sub(self, rhs: &Polynomial<T>) -> Self::Output {
+        let len = self.coeffs.len().max(rhs.coeffs.len());
+        let coeffs = (0..len)
+            .map(|i| {
+                let a = self.coeffs.get(i).cloned().unwrap_or_else(T::zero);
+                let b = rhs.coeffs.get(i).cloned().unwrap_or_else(T::zero);
+                a - b
+            })
+            .collect();
+        Polynomial::new(coeffs)
+    }
+}
+
+impl <T: Zero + Clone + Mul<T, Output = T>> Mul for &Polynomial<T> {
+    type Output = Polynomial<T>;
+
+    fn mul(self, rhs: &Polynomial<T>) -> Self::Output {
+        if self.coeffs.is_empty() || rhs.coeffs.is_empty() {
+            return Polynomial::new(Vec::new());
+        }
+        let mut coeffs = vec![T::zero(); self.coeffs.len() + rhs.coeffs.len() - 1];
+        for (i, a) in self.coeffs.iter().enumerate() {
+            for (j, b) in rhs.coeffs.iter().enumerate() {
+                coeffs[i + j] = coeffs[i + j].clone() + a.clone() * b.clone();
+            }
+        }
+        Polynomial::new(coeffs)
+    }
+}
+
+impl <T: Zero + One + PartialEq + Signed + fmt::Display> fmt::Display for Polynomial<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.coeffs.is_empty() {
+            return write!(f, "0");
+        }
+        let mut first = true;
+        for (power, c) in self.coeffs.iter().enumerate().rev() {
+            if c.is_zero() {
+                continue;
+            }
+            if first {
+                if c.is_negative() {
+                    write!(f, "-")?;
+                }
+            } else if c.is_negative() {
+                write!(f, " - ")?;
+            } else {
+                write!(f, " + ")?;
+            }
+            first = false;
+
+            // Fractional coefficients are parenthesized so that `(1/2)x`
+            // doesn't read as `1/(2x)`.
+            let abs = c.abs();
+            let text = abs.to_string();
+            if power > 0 && text.contains('/') {
+                write!(f, "({})", text)?;
+            } else if abs != T::one() || power == 0 {
+                write!(f, "{}", text)?;
+            }
+            match power {
+                0 => (),
+                1 => write!(f, "x")?,
+                _ => write!(f, "x^{}", power)?
+            }
+        }
+        Ok(())
+    }
+}
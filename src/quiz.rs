@@ -0,0 +1,195 @@
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use matrix::{Field, Matrix, MatrixResult};
+use reduce::row_reduce;
+use storage::data_dir;
+use ui::command::Command;
+
+/// Tracks someone row reducing a matrix by hand.
+pub struct Quiz<T> {
+    pub problem: Matrix<T>,
+    answer: Matrix<T>,
+    /// How many steps `row_reduce` takes, to compare against.
+    optimal_moves: usize,
+    moves: usize,
+    invalid_moves: usize,
+    checks: usize
+}
+
+impl <T: Field + fmt::Display> Quiz<T> {
+
+    pub fn new(problem: Matrix<T>) -> MatrixResult<Quiz<T>> {
+        let reduction = row_reduce(&problem)?;
+        Ok(Quiz {
+            problem,
+            answer: reduction.matrix,
+            optimal_moves: reduction.steps.len(),
+            moves: 0,
+            invalid_moves: 0,
+            checks: 0
+        })
+    }
+
+    /// Checks that the command is a row operation that can't lose
+    /// information. Invalid moves shouldn't be applied.
+    pub fn check_move(&self, cmd: &Command<T>) -> Result<(), String> {
+        match cmd {
+            &Command::EditCell { .. } | &Command::EditRow { .. } =>
+                Err("only row operations are allowed".to_owned()),
+            _ => cmd.check_invertible()
+        }
+    }
+
+    /// Counts a move once it has been applied.
+    pub fn record_move(&mut self) {
+        self.moves += 1;
+    }
+
+    /// Counts a move that `check_move` rejected.
+    pub fn record_invalid_move(&mut self) {
+        self.invalid_moves += 1;
+    }
+
+    /// Checks whether the matrix is the RREF of the problem, and if not,
+    /// describes what is wrong with it.
+    pub fn check(&mut self, matrix: &Matrix<T>) -> Vec<String> {
+        self.checks += 1;
+        let mut mistakes = rref_mistakes(matrix);
+        if mistakes.is_empty() && *matrix != self.answer {
+            mistakes.push("The matrix is in RREF, but it isn't row equivalent to the problem".to_owned());
+        }
+        mistakes
+    }
+
+    pub fn moves(&self) -> usize {
+        self.moves
+    }
+
+    pub fn invalid_moves(&self) -> usize {
+        self.invalid_moves
+    }
+
+    /// Summarizes the quiz for the scores file.
+    pub fn score(&self, time: u64, solved: bool) -> Score {
+        Score {
+            time,
+            rows: self.problem.rows,
+            cols: self.problem.cols,
+            moves: self.moves,
+            optimal_moves: self.optimal_moves,
+            invalid_moves: self.invalid_moves,
+            checks: self.checks,
+            solved
+        }
+    }
+}
+
+/// Describes every way in which the matrix breaks the rules of reduced row
+/// echelon form.
+pub fn rref_mistakes<T: Field + fmt::Display>(matrix: &Matrix<T>) -> Vec<String> {
+    let mut mistakes = Vec::new();
+    let mut prev = 0;
+    let mut zero_row = None;
+    for (r, entry) in matrix.leading_entries().into_iter().enumerate() {
+        let row = r + 1;
+        let col = match entry {
+            Some(col) => col,
+            None => {
+                zero_row = zero_row.or(Some(row));
+                continue;
+            }
+        };
+        if let Some(z) = zero_row {
+            mistakes.push(format!("Row {} is below row {}, which is all zeros", row, z));
+        } else if col <= prev {
+            mistakes.push(format!("The leading entry of row {} isn't right of the one above it", row));
+        }
+        prev = prev.max(col);
+
+        let value = matrix.get(row, col).unwrap();
+        if value != T::one() {
+            mistakes.push(format!("The leading entry of row {} is {}, not 1", row, value));
+        }
+        for other in (1..(matrix.rows + 1)).filter(|&o| o != row) {
+            if !matrix.get(other, col).unwrap().is_zero() {
+                mistakes.push(format!("Column {} should be zero in row {}, since row {} has its pivot there",
+                                      col, other, row));
+            }
+        }
+    }
+    mistakes
+}
+
+/// The result of one quiz.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Score {
+    /// When the quiz ended, in seconds since the Unix epoch.
+    pub time: u64,
+    pub rows: usize,
+    pub cols: usize,
+    pub moves: usize,
+    pub optimal_moves: usize,
+    pub invalid_moves: usize,
+    /// How many times the answer was checked.
+    pub checks: usize,
+    pub solved: bool
+}
+
+impl Score {
+
+    /// Formats the score as one tab separated line, without the newline.
+    pub fn to_line(&self) -> String {
+        format!("{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}", self.time, self.rows, self.cols, self.moves,
+                self.optimal_moves, self.invalid_moves, self.checks, self.solved)
+    }
+
+    pub fn from_line(line: &str) -> Result<Score, String> {
+        let fields: Vec<&str> = line.trim().split('\t').collect();
+        if fields.len() != 8 {
+            return Err(format!("expected 8 fields, found {}", fields.len()));
+        }
+        let number = |i: usize| usize::from_str(fields[i]).map_err(|_| format!("invalid field: {}", fields[i]));
+        Ok(Score {
+            time: u64::from_str(fields[0]).map_err(|_| format!("invalid time: {}", fields[0]))?,
+            rows: number(1)?,
+            cols: number(2)?,
+            moves: number(3)?,
+            optimal_moves: number(4)?,
+            invalid_moves: number(5)?,
+            checks: number(6)?,
+            solved: bool::from_str(fields[7]).map_err(|_| format!("invalid field: {}", fields[7]))?
+        })
+    }
+}
+
+/// Where scores are saved by default.
+pub fn scores_path() -> Result<PathBuf, String> {
+    Ok(data_dir()?.join("scores.tsv"))
+}
+
+/// Appends the score to the file, creating it and its directory if needed.
+pub fn save_score(path: &Path, score: &Score) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path).map_err(|e| e.to_string())?;
+    writeln!(file, "{}", score.to_line()).map_err(|e| e.to_string())
+}
+
+/// Reads every score saved in the file. A missing file has no scores.
+pub fn load_scores(path: &Path) -> Result<Vec<Score>, String> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(_) if !path.exists() => return Ok(Vec::new()),
+        Err(e) => return Err(e.to_string())
+    };
+    BufReader::new(file)
+        .lines()
+        .filter(|line| line.as_ref().map(|l| !l.trim().is_empty()).unwrap_or(true))
+        .map(|line| line.map_err(|e| e.to_string()).and_then(|l| Score::from_line(&l)))
+        .collect()
+}
@@ -0,0 +1,61 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A small, seedable pseudorandom number generator (xorshift64*), so that
+/// generated matrices can be reproduced from their seed.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64
+}
+
+impl Rng {
+
+    pub fn new(seed: u64) -> Rng {
+        // The generator gets stuck at zero, so replace it with an arbitrary
+        // nonzero state.
+        let state = if seed == 0 { 0x9e37_79b9_7f4a_7c15 } else { seed };
+        Rng { state }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// Generates an integer between `min` and `max`, inclusive.
+    pub fn range(&mut self, min: i64, max: i64) -> i64 {
+        assert!(min <= max);
+        // The difference always fits in a u64, but one more may not.
+        match (max.wrapping_sub(min) as u64).checked_add(1) {
+            Some(span) => min.wrapping_add((self.next_u64() % span) as i64),
+            None => self.next_u64() as i64
+        }
+    }
+
+    /// Generates an integer between `min` and `max`, inclusive, that is not
+    /// zero. The range must contain a nonzero integer.
+    pub fn nonzero_range(&mut self, min: i64, max: i64) -> i64 {
+        assert!(min != 0 || max != 0);
+        loop {
+            let n = self.range(min, max);
+            if n != 0 {
+                return n;
+            }
+        }
+    }
+
+    /// Shuffles the slice in place.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.range(0, i as i64) as usize;
+            items.swap(i, j);
+        }
+    }
+}
+
+/// A seed that changes every time, for when none is given.
+pub fn time_seed() -> u64 {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    now.as_secs() ^ now.subsec_nanos() as u64
+}
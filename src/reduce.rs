@@ -0,0 +1,226 @@
+use std::fmt;
+use num_traits::{One, Zero};
+
+use matrix::{Field, Matrix, MatrixResult};
+use ui::command::{ApplyMode, Command};
+
+/// How far along a matrix is in being row reduced.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum EchelonForm {
+    /// Reduced row echelon form.
+    Reduced,
+    /// Row echelon form, but not reduced.
+    Echelon,
+    /// Not in row echelon form.
+    Neither
+}
+
+impl fmt::Display for EchelonForm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match *self {
+            EchelonForm::Reduced => "RREF",
+            EchelonForm::Echelon => "REF",
+            EchelonForm::Neither => "not in REF"
+        };
+        write!(f, "{}", text)
+    }
+}
+
+/// The result of reducing a matrix to reduced row echelon form.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Reduction<T> {
+    /// The matrix in reduced row echelon form.
+    pub matrix: Matrix<T>,
+    /// The row operations that were applied, in order.
+    pub steps: Vec<Command<T>>,
+    /// The (1-based) pivot column of each nonzero row.
+    pub pivots: Vec<usize>
+}
+
+impl <T: Field> Reduction<T> {
+
+    /// The rank of the reduced matrix.
+    pub fn rank(&self) -> usize {
+        self.pivots.len()
+    }
+
+    /// The (1-based) columns that do not contain a pivot.
+    pub fn free_columns(&self) -> Vec<usize> {
+        (1..(self.matrix.cols + 1))
+            .filter(|c| !self.pivots.contains(c))
+            .collect()
+    }
+
+    /// A basis for the solutions of `Ax = 0`, with one vector per free
+    /// column. Each vector has a one in its free column.
+    pub fn kernel_basis(&self) -> Vec<Vec<T>> {
+        self.free_columns()
+            .into_iter()
+            .map(|free| {
+                let mut v = vec![T::zero(); self.matrix.cols];
+                v[free - 1] = T::one();
+                for (i, &pivot) in self.pivots.iter().enumerate() {
+                    let entry = self.matrix.get(i + 1, free).unwrap();
+                    v[pivot - 1] = -entry;
+                }
+                v
+            })
+            .collect()
+    }
+}
+
+impl <T: Clone + PartialEq + Zero + One> Matrix<T> {
+
+    /// Gets the (1-based) column of the first nonzero entry of each row, or
+    /// `None` for rows that are entirely zero.
+    pub fn leading_entries(&self) -> Vec<Option<usize>> {
+        (1..(self.rows + 1))
+            .map(|r| {
+                self.row(r).unwrap()
+                    .iter()
+                    .position(|x| !x.is_zero())
+                    .map(|c| c + 1)
+            })
+            .collect()
+    }
+
+    /// Determines whether the matrix is in reduced row echelon form, row
+    /// echelon form, or neither.
+    pub fn echelon_form(&self) -> EchelonForm {
+        let leading = self.leading_entries();
+
+        // Each leading entry must be to the right of the one above it, and
+        // zero rows must be at the bottom.
+        let mut prev = 0;
+        let mut seen_zero_row = false;
+        for entry in &leading {
+            match *entry {
+                Some(col) if !seen_zero_row && col > prev => prev = col,
+                Some(_) => return EchelonForm::Neither,
+                None => seen_zero_row = true
+            }
+        }
+
+        let reduced = leading.iter()
+            .enumerate()
+            .filter_map(|(r, entry)| entry.map(|c| (r + 1, c)))
+            .all(|(row, col)| {
+                self.get(row, col).unwrap() == T::one() &&
+                    (1..(self.rows + 1)).all(|r| r == row || self.get(r, col).unwrap().is_zero())
+            });
+        if reduced { EchelonForm::Reduced } else { EchelonForm::Echelon }
+    }
+}
+
+impl <T: Field> Matrix<T> {
+
+    /// The number of pivots in the reduced row echelon form.
+    pub fn rank(&self) -> MatrixResult<usize> {
+        Ok(row_reduce(self)?.rank())
+    }
+
+    /// Computes the determinant from the steps that reduce the matrix to the
+    /// identity: every swap flips its sign, and scaling by `c` divides it by
+    /// `c`. Fails if the matrix isn't square.
+    pub fn determinant(&self) -> MatrixResult<T> {
+        if !self.is_square() {
+            return Err(format!("a {}x{} matrix has no determinant", self.rows, self.cols));
+        }
+        let reduction = row_reduce(self)?;
+        if reduction.rank() < self.rows {
+            return Ok(T::zero());
+        }
+        Ok(reduction.steps.into_iter().fold(T::one(), |det, step| match step {
+            Command::SwapRow { .. } => -det,
+            Command::ScaleRow { coeff, .. } => det / coeff,
+            _ => det
+        }))
+    }
+
+    /// The (1-based) columns that contain a pivot in the reduced row echelon
+    /// form of the matrix.
+    pub fn pivot_columns(&self) -> MatrixResult<Vec<usize>> {
+        Ok(row_reduce(self)?.pivots)
+    }
+
+    /// A basis for the null space, which contains the solutions of `Ax = 0`.
+    pub fn null_space(&self) -> MatrixResult<Vec<Vec<T>>> {
+        Ok(row_reduce(self)?.kernel_basis())
+    }
+
+    /// A basis for the column space, made of the pivot columns of the
+    /// original matrix.
+    pub fn column_space(&self) -> MatrixResult<Vec<Vec<T>>> {
+        Ok(self.pivot_columns()?
+            .into_iter()
+            .map(|c| self.get_col(c).unwrap())
+            .collect())
+    }
+
+    /// A basis for the row space, made of the nonzero rows of the reduced row
+    /// echelon form. The vectors are returned as columns.
+    pub fn row_space(&self) -> MatrixResult<Vec<Vec<T>>> {
+        let reduction = row_reduce(self)?;
+        Ok((1..(reduction.rank() + 1))
+            .map(|r| reduction.matrix.get_row(r).unwrap())
+            .collect())
+    }
+
+    /// A basis for the left null space, which contains the solutions of
+    /// `A^T y = 0`.
+    pub fn left_null_space(&self) -> MatrixResult<Vec<Vec<T>>> {
+        self.transpose().null_space()
+    }
+}
+
+/// Reduces the matrix to reduced row echelon form by applying `Command`s,
+/// recording each one that was applied.
+pub fn row_reduce<T: Field>(matrix: &Matrix<T>) -> MatrixResult<Reduction<T>> {
+    let mut current = matrix.clone();
+    let mut steps = Vec::new();
+    let mut pivots = Vec::new();
+    let mut row = 1;
+
+    {
+        let mut apply = |current: &mut Matrix<T>, cmd: Command<T>| -> MatrixResult<()> {
+            cmd.apply_in_place(current, ApplyMode::Strict)?;
+            steps.push(cmd);
+            Ok(())
+        };
+
+        for col in 1..(matrix.cols + 1) {
+            if row > matrix.rows {
+                break;
+            }
+            let found = (row..(matrix.rows + 1))
+                .find(|&r| !current.get(r, col).unwrap().is_zero());
+            let pivot_row = match found {
+                Some(r) => r,
+                None => continue
+            };
+
+            if pivot_row != row {
+                apply(&mut current, Command::SwapRow { row1: row, row2: pivot_row })?;
+            }
+            let pivot = current.get(row, col).unwrap();
+            if pivot != T::one() {
+                apply(&mut current, Command::ScaleRow { coeff: T::one() / pivot, row })?;
+            }
+            for other in 1..(matrix.rows + 1) {
+                let entry = current.get(other, col).unwrap();
+                if other != row && !entry.is_zero() {
+                    apply(&mut current, Command::AddRow { coeff: -entry, src: row, dest: other })?;
+                }
+            }
+
+            pivots.push(col);
+            row += 1;
+        }
+    }
+
+    Ok(Reduction {
+        matrix: current,
+        steps,
+        pivots
+    })
+}
@@ -0,0 +1,101 @@
+use std::fmt::Display;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use matrix::{Matrix, MatrixResult};
+use parse::parse_matrix;
+use storage::data_dir;
+use ui::ColumnLabels;
+use ui::command::Command;
+
+/// Everything needed to pick up where the TUI left off.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Session<T> {
+    pub matrix: Matrix<T>,
+    /// The commands applied so far, oldest first.
+    pub history: Vec<Command<T>>,
+    pub column_labels: ColumnLabels,
+    pub highlight_changes: bool
+}
+
+impl <T> Session<T> {
+
+    /// Writes the session as text, with the matrix one row per line and the
+    /// history one command per line.
+    pub fn to_text(&self) -> String where T: Clone + Display {
+        let labels = match self.column_labels {
+            ColumnLabels::Numbered => "numbered",
+            ColumnLabels::Variables => "variables"
+        };
+        let mut text = format!("labels: {}\nhighlight: {}\nmatrix:\n", labels, self.highlight_changes);
+        for row in self.matrix.rows() {
+            let entries: Vec<String> = row.iter().map(|x| x.to_string()).collect();
+            text.push_str(&entries.join(" "));
+            text.push('\n');
+        }
+        text.push_str("history:\n");
+        for cmd in &self.history {
+            text.push_str(&format!("{}\n", cmd));
+        }
+        text
+    }
+
+    pub fn from_text(text: &str) -> MatrixResult<Session<T>> where T: FromStr {
+        let mut lines = text.lines();
+        let mut field = |name: &str| {
+            let line = lines.next().unwrap_or("");
+            match line.strip_prefix(name) {
+                Some(value) => Ok(value.trim().to_owned()),
+                None => Err(format!("expected {}", name))
+            }
+        };
+        let column_labels = match field("labels:")?.as_str() {
+            "numbered" => ColumnLabels::Numbered,
+            "variables" => ColumnLabels::Variables,
+            labels => return Err(format!("unknown labels: {}", labels))
+        };
+        let highlight_changes = bool::from_str(&field("highlight:")?).map_err(|e| e.to_string())?;
+        field("matrix:")?;
+
+        let rest: Vec<&str> = lines.collect();
+        let split = rest.iter().position(|&line| line == "history:").ok_or("expected history:".to_owned())?;
+        let matrix = parse_matrix(&rest[..split].join("\n"))?;
+        let history = rest[(split + 1)..]
+            .iter()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| Command::from_str(line))
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(Session {
+            matrix,
+            history,
+            column_labels,
+            highlight_changes
+        })
+    }
+
+    /// Saves the session, replacing the file in one step so that a crash
+    /// can't leave it half written.
+    pub fn save(&self, path: &Path) -> MatrixResult<()> where T: Clone + Display {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, self.to_text()).map_err(|e| e.to_string())?;
+        fs::rename(&tmp, path).map_err(|e| e.to_string())
+    }
+
+    /// Loads the saved session, if there is one.
+    pub fn load(path: &Path) -> MatrixResult<Option<Session<T>>> where T: FromStr {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        Session::from_text(&text).map(Some)
+    }
+}
+
+/// Where the TUI saves its session.
+pub fn session_path() -> MatrixResult<PathBuf> {
+    Ok(data_dir()?.join("session.txt"))
+}
@@ -0,0 +1,28 @@
+use std::env;
+use std::path::PathBuf;
+
+/// The directory where matrops keeps its files: `$XDG_DATA_HOME/matrops`, or
+/// `~/.local/share/matrops` if that isn't set.
+pub fn data_dir() -> Result<PathBuf, String> {
+    let base = match env::var_os("XDG_DATA_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => {
+            let home = env::var_os("HOME").ok_or("HOME is not set".to_owned())?;
+            PathBuf::from(home).join(".local").join("share")
+        }
+    };
+    Ok(base.join("matrops"))
+}
+
+/// The directory where matrops looks for its config file:
+/// `$XDG_CONFIG_HOME/matrops`, or `~/.config/matrops` if that isn't set.
+pub fn config_dir() -> Result<PathBuf, String> {
+    let base = match env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => {
+            let home = env::var_os("HOME").ok_or("HOME is not set".to_owned())?;
+            PathBuf::from(home).join(".config")
+        }
+    };
+    Ok(base.join("matrops"))
+}
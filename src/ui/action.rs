@@ -0,0 +1,148 @@
+/// Everything that can be done from the matrix screen with a key.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum Action {
+    Scale,
+    Swap,
+    Add,
+    Edit,
+    EditRow,
+    Eliminate,
+    Normalize,
+    Permute,
+    GramSchmidt,
+    Eigen,
+    Subspaces,
+    ToggleVariables,
+    ToggleDiff,
+    Record,
+    Replay,
+    Export,
+    NewMatrix,
+    Help
+}
+
+impl Action {
+
+    pub fn all() -> Vec<Action> {
+        vec![Action::Scale, Action::Swap, Action::Add, Action::Edit, Action::EditRow,
+             Action::Eliminate, Action::Normalize, Action::Permute, Action::GramSchmidt,
+             Action::Eigen, Action::Subspaces, Action::ToggleVariables, Action::ToggleDiff,
+             Action::Record, Action::Replay, Action::Export, Action::NewMatrix, Action::Help]
+    }
+
+    /// The name of the action in the config file.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Action::Scale => "scale",
+            Action::Swap => "swap",
+            Action::Add => "add",
+            Action::Edit => "edit",
+            Action::EditRow => "edit_row",
+            Action::Eliminate => "eliminate",
+            Action::Normalize => "normalize",
+            Action::Permute => "permute",
+            Action::GramSchmidt => "gram_schmidt",
+            Action::Eigen => "eigen",
+            Action::Subspaces => "subspaces",
+            Action::ToggleVariables => "toggle_variables",
+            Action::ToggleDiff => "toggle_diff",
+            Action::Record => "record",
+            Action::Replay => "replay",
+            Action::Export => "export",
+            Action::NewMatrix => "new_matrix",
+            Action::Help => "help"
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Action> {
+        Action::all().into_iter().find(|action| action.name() == name)
+    }
+
+    /// What the action does, for listing the keys.
+    pub fn description(&self) -> &'static str {
+        match *self {
+            Action::Scale => "Multiply a row by a scalar",
+            Action::Swap => "Interchange two rows",
+            Action::Add => "Add a multiple of a row to another row",
+            Action::Edit => "Edit one cell",
+            Action::EditRow => "Edit a row",
+            Action::Eliminate => "Clear a column using a pivot",
+            Action::Normalize => "Make a row's leading entry 1",
+            Action::Permute => "Reorder the rows",
+            Action::GramSchmidt => "Step through Gram-Schmidt on the columns",
+            Action::Eigen => "Show eigenvalues and eigenvectors",
+            Action::Subspaces => "Show bases of the four fundamental subspaces",
+            Action::ToggleVariables => "Label columns as the variables of a linear system",
+            Action::ToggleDiff => "Toggle highlighting of changed cells",
+            Action::Record => "Start or stop recording operations",
+            Action::Replay => "Play a recording back",
+            Action::Export => "Export the matrix",
+            Action::NewMatrix => "Enter a new matrix",
+            Action::Help => "Show the keys"
+        }
+    }
+
+    /// A short name for the action, for menus.
+    pub fn label(&self) -> &'static str {
+        match *self {
+            Action::Scale => "Scale row",
+            Action::Swap => "Swap rows",
+            Action::Add => "Add rows",
+            Action::Edit => "Edit cell",
+            Action::EditRow => "Edit row",
+            Action::Eliminate => "Clear column",
+            Action::Normalize => "Normalize pivot",
+            Action::Permute => "Reorder rows",
+            Action::GramSchmidt => "Gram-Schmidt",
+            Action::Eigen => "Eigenvalues",
+            Action::Subspaces => "Subspaces",
+            Action::ToggleVariables => "Variable labels",
+            Action::ToggleDiff => "Highlight changes",
+            Action::Record => "Record",
+            Action::Replay => "Replay",
+            Action::Export => "Export",
+            Action::NewMatrix => "New matrix",
+            Action::Help => "Keys"
+        }
+    }
+
+    /// The menus of the menubar, from left to right.
+    pub fn menus() -> Vec<&'static str> {
+        vec!["File", "Edit", "Operations", "Analyze", "View"]
+    }
+
+    /// The menu that the action is listed in.
+    pub fn menu(&self) -> &'static str {
+        match *self {
+            Action::NewMatrix | Action::Export | Action::Record | Action::Replay => "File",
+            Action::Edit | Action::EditRow => "Edit",
+            Action::Scale | Action::Swap | Action::Add | Action::Eliminate | Action::Normalize |
+            Action::Permute => "Operations",
+            Action::GramSchmidt | Action::Eigen | Action::Subspaces => "Analyze",
+            Action::ToggleVariables | Action::ToggleDiff | Action::Help => "View"
+        }
+    }
+
+    pub fn default_key(&self) -> char {
+        match *self {
+            Action::Scale => 's',
+            Action::Swap => 'i',
+            Action::Add => 'a',
+            Action::Edit => 'e',
+            Action::EditRow => 'E',
+            Action::Eliminate => 'c',
+            Action::Normalize => 'l',
+            Action::Permute => 'P',
+            Action::GramSchmidt => 'g',
+            Action::Eigen => 'v',
+            Action::Subspaces => 'b',
+            Action::ToggleVariables => 'x',
+            Action::ToggleDiff => 'd',
+            Action::Record => 'r',
+            Action::Replay => 'p',
+            Action::Export => 'o',
+            Action::NewMatrix => 'n',
+            Action::Help => '?'
+        }
+    }
+}
@@ -0,0 +1,1098 @@
+use cursive::Cursive;
+use cursive::event::{Event, Key};
+use cursive::menu::MenuTree;
+use cursive::view::{Offset, Position};
+use cursive::views;
+use cursive::traits::Identifiable;
+use num_rational::Ratio;
+use num_traits::FromPrimitive;
+use std::cell::{Cell, RefCell};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use config::{Config, Notation};
+use eigen::is_real;
+use exercise::{generate, ExerciseOptions};
+use export::{export, export_with_subspaces, ExportFormat};
+use expr::eval;
+use matrix::Matrix;
+use orthogonal::Projection;
+use parse::parse_matrix_with;
+use quiz::{save_score, scores_path, Quiz};
+use random::{time_seed, Rng};
+use session::{session_path, Session};
+use storage::data_dir;
+use ui::{open_error_popup, Action, ColumnLabels, Form, MatrixView, StatusBar};
+use ui::command::{ApplyMode, Command, CommandSequence};
+use ui::format::decimal;
+
+/// Sets up the app on the screen: the status bar, the menubar and the setup
+/// screen, which leads to everything else. `run` still has to be called.
+pub fn start(s: &mut Cursive, config: Config) {
+    CONFIG.with(|c| *c.borrow_mut() = config);
+    QUIZ.with(|q| q.borrow_mut().take());
+    RECORDING.with(|r| r.borrow_mut().take());
+
+    let status_bar = views::BoxView::with_full_width(StatusBar::new().with_id("status_bar"));
+    let screen_size = s.screen_size();
+    s.screen_mut().add_layer_at(Position::new(
+        Offset::Absolute(screen_size.x), Offset::Absolute(screen_size.y + 1)), status_bar);
+    let (notation, precision) = CONFIG.with(|c| (c.borrow().notation, c.borrow().precision));
+    s.call_on_id("status_bar", |bar: &mut StatusBar| bar.set_notation(notation, precision));
+
+    // Redrawing is only needed while something fades out.
+    s.add_global_callback(Event::Refresh, stop_fading_when_idle);
+
+    add_menubar(s);
+    show_setup_view(s);
+}
+
+thread_local! {
+    /// The settings from the config file.
+    static CONFIG: RefCell<Config> = RefCell::new(Config::default());
+}
+
+/// Adds a menu of every action, grouped like `Action::menu`, which Escape
+/// opens.
+fn add_menubar(s: &mut Cursive) {
+    let bindings = CONFIG.with(|c| c.borrow().bindings());
+    for menu in Action::menus() {
+        let mut tree = bindings.iter()
+            .filter(|&&(action, _)| action.menu() == menu)
+            .fold(MenuTree::new(), |tree, &(action, key)| {
+                tree.leaf(format!("{} ({})", action.label(), key), move |s| run_action(s, action))
+            });
+        if menu == "File" {
+            tree = tree.delimiter().leaf("Quit", quit);
+        }
+        s.menubar().add_subtree(menu, tree);
+    }
+    s.set_autohide_menu(false);
+    s.add_global_callback(Key::Esc, |s| s.select_menubar());
+}
+
+/// Runs an action from the menu, which can be opened before there is a
+/// matrix to act on.
+pub fn run_action(s: &mut Cursive, action: Action) {
+    if action != Action::Help && s.find_id::<MatrixView<Ratio<i64>>>("matrix_view").is_none() {
+        return open_error_popup(s, "Enter a matrix first");
+    }
+    action_callback(action)(s);
+}
+
+/// Lists every key, grouped by menu.
+fn help_action(s: &mut Cursive) {
+    let bindings = CONFIG.with(|c| c.borrow().bindings());
+    let mut text = String::new();
+    for menu in Action::menus() {
+        text.push_str(&format!("{}\n", menu));
+        for &(action, key) in bindings.iter().filter(|&&(action, _)| action.menu() == menu) {
+            text.push_str(&format!("  {}  {}\n", key, action.description()));
+        }
+        text.push('\n');
+    }
+    text.push_str("Press Esc to open the menu, and use the arrow keys and Page Up/Down to scroll the matrix.");
+    s.add_layer(views::Dialog::around(views::TextView::new(text).with_id("help_text"))
+        .title("Keys")
+        .dismiss_button("Close"));
+}
+
+/// The current time in seconds since the Unix epoch.
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+fn show_setup_view(s: &mut Cursive) {
+    let row_text = views::EditView::new().with_id("rows");
+    let col_text = views::EditView::new().with_id("columns");
+    let paste_text = views::BoxView::with_fixed_size((40, 6), views::TextArea::new().with_id("paste"));
+    let setup_pane = views::LinearLayout::vertical()
+        .child(views::ListView::new()
+            .child("Rows", row_text)
+            .child("Columns", col_text))
+        .child(views::DummyView {})
+        .child(views::TextView::new("Or paste a matrix, such as [1 2; 3 4]:"))
+        .child(paste_text);
+    let setup_dialog = views::Dialog::around(setup_pane)
+        .title("Enter matrix dimensions")
+        .button("Go", |s| {
+            let pasted = s.find_id::<views::TextArea>("paste")
+                .expect("Can't find paste TextArea")
+                .get_content()
+                .to_owned();
+            if !pasted.trim().is_empty() {
+                match parse_matrix_with(&pasted, eval::<Ratio<i64>>) {
+                    Ok(matrix) => {
+                        s.pop_layer();
+                        show_edit_view(s, matrix);
+                    },
+                    Err(e) => open_error_popup(s, e)
+                }
+                return;
+            }
+
+            let rt = s.find_id::<views::EditView>("rows").expect("Can't find row EditText");
+            let ct = s.find_id::<views::EditView>("columns").expect("Can't find col EditText");
+            let rows = usize::from_str(&rt.get_content());
+            let cols = usize::from_str(&ct.get_content());
+
+            let mut show_error = true;
+            if let (Ok(rows), Ok(cols)) = (rows, cols) {
+                if rows > 0 && cols > 0 {
+                    let data = vec![Ratio::from_i64(0).unwrap(); rows * cols];
+                    let matrix = Matrix::new(rows, cols, data);
+                    s.pop_layer();
+                    show_edit_view(s, matrix);
+                    show_error = false;
+                }
+            }
+
+            if show_error {
+                open_error_popup(s, "Please enter positive integers");
+            }
+        })
+        .button("New from template", show_template_list)
+        .button("Quiz", start_quiz);
+
+    // Offer to pick up where the last session left off.
+    let setup_dialog = match session_path().and_then(|path| Session::load(&path)) {
+        Ok(Some(session)) => {
+            let label = format!("Restore last session ({}x{}, {} operations)",
+                                session.matrix.rows, session.matrix.cols, session.history.len());
+            setup_dialog.button(label, move |s| {
+                s.pop_layer();
+                restore_session(s, session.clone());
+            })
+        },
+        Ok(None) => setup_dialog,
+        Err(e) => {
+            show_error(s, format!("Couldn't load the last session: {}", e));
+            setup_dialog
+        }
+    };
+    s.add_layer(setup_dialog);
+}
+
+thread_local! {
+    /// The quiz being taken, if any. Commands are checked by it before they
+    /// are applied, and counted once they are.
+    static QUIZ: RefCell<Option<Quiz<Ratio<i64>>>> = const { RefCell::new(None) };
+}
+
+/// Starts a quiz on a random matrix, using the size on the setup screen if
+/// one was entered.
+fn start_quiz(s: &mut Cursive) {
+    let rows = usize::from_str(&s.find_id::<views::EditView>("rows").expect("Can't find row EditText").get_content());
+    let cols = usize::from_str(&s.find_id::<views::EditView>("columns").expect("Can't find col EditText").get_content());
+    let options = match (rows, cols) {
+        (Ok(rows), Ok(cols)) => ExerciseOptions::new(rows, cols),
+        _ => ExerciseOptions::new(3, 4)
+    };
+    let quiz = generate(&options, &mut Rng::new(time_seed())).and_then(|e| Quiz::new(e.problem));
+    match quiz {
+        Ok(quiz) => {
+            let problem = quiz.problem.clone();
+            QUIZ.with(|q| *q.borrow_mut() = Some(quiz));
+            s.pop_layer();
+            show_edit_view(s, problem);
+            show_info(s, "Reduce the matrix to RREF, then press Done");
+        },
+        Err(e) => open_error_popup(s, e)
+    }
+}
+
+/// Checks the answer to the quiz. Once it is right, the score is saved and
+/// the quiz ends.
+fn finish_quiz_action(s: &mut Cursive) {
+    let matrix = s.find_id::<MatrixView<Ratio<i64>>>("matrix_view")
+        .expect("matrix view not found")
+        .matrix
+        .clone();
+    let mistakes = QUIZ.with(|q| q.borrow_mut().as_mut().map(|quiz| quiz.check(&matrix)));
+    let mistakes = match mistakes {
+        Some(mistakes) => mistakes,
+        None => return
+    };
+    if !mistakes.is_empty() {
+        let text = format!("Not quite:\n\n{}", mistakes.join("\n"));
+        s.add_layer(views::Dialog::around(views::TextView::new(text).with_id("quiz_text"))
+            .title("Quiz")
+            .dismiss_button("Keep going"));
+        return;
+    }
+
+    let quiz = QUIZ.with(|q| q.borrow_mut().take()).expect("quiz not found");
+    update_status(s);
+    let score = quiz.score(now_secs(), true);
+    let mut text = format!("Correct! You used {} moves ({} invalid); the computed reduction takes {}.",
+                           score.moves, score.invalid_moves, score.optimal_moves);
+    if let Err(e) = scores_path().and_then(|path| save_score(&path, &score)) {
+        text.push_str(&format!("\n\nThe score couldn't be saved: {}", e));
+    }
+    s.add_layer(views::Dialog::around(views::TextView::new(text).with_id("quiz_text"))
+        .title("Quiz")
+        .dismiss_button("Close"));
+}
+
+/// Ends the quiz being taken, if any, without solving it. Its score is saved
+/// as unsolved.
+fn abandon_quiz(s: &mut Cursive) {
+    if let Some(quiz) = QUIZ.with(|q| q.borrow_mut().take()) {
+        let score = quiz.score(now_secs(), false);
+        if let Err(e) = scores_path().and_then(|path| save_score(&path, &score)) {
+            show_error(s, format!("The quiz score couldn't be saved: {}", e));
+        }
+    }
+}
+
+fn quit(s: &mut Cursive) {
+    abandon_quiz(s);
+    s.quit();
+}
+
+/// The kinds of matrices that can be generated from the setup screen.
+#[derive(Clone, Copy)]
+enum Template {
+    Identity,
+    Zero,
+    Diagonal,
+    RandomIntegers,
+    RandomRank,
+    RandomInvertible,
+    Hilbert,
+    Vandermonde,
+    Tridiagonal
+}
+
+impl Template {
+
+    fn all() -> Vec<Template> {
+        vec![Template::Identity, Template::Zero, Template::Diagonal, Template::RandomIntegers,
+             Template::RandomRank, Template::RandomInvertible, Template::Hilbert,
+             Template::Vandermonde, Template::Tridiagonal]
+    }
+
+    fn name(&self) -> &'static str {
+        match *self {
+            Template::Identity => "Identity",
+            Template::Zero => "Zero",
+            Template::Diagonal => "Diagonal",
+            Template::RandomIntegers => "Random integers",
+            Template::RandomRank => "Random with given rank",
+            Template::RandomInvertible => "Random with integer inverse",
+            Template::Hilbert => "Hilbert",
+            Template::Vandermonde => "Vandermonde",
+            Template::Tridiagonal => "Tridiagonal"
+        }
+    }
+
+    /// The labels of the parameters the template needs.
+    fn fields(&self) -> Vec<&'static str> {
+        match *self {
+            Template::Identity | Template::Hilbert => vec!["Size"],
+            Template::Zero => vec!["Rows", "Columns"],
+            Template::Diagonal => vec!["Diagonal entries"],
+            Template::Vandermonde => vec!["Values"],
+            Template::RandomIntegers => vec!["Rows", "Columns", "Minimum", "Maximum", "Seed"],
+            Template::RandomRank => vec!["Rows", "Columns", "Rank", "Largest factor entry", "Seed"],
+            Template::RandomInvertible => vec!["Size", "Largest factor entry", "Seed"],
+            Template::Tridiagonal => vec!["Size", "Below diagonal", "Diagonal", "Above diagonal"]
+        }
+    }
+
+    /// Generates the matrix from the entered parameters, which are in the
+    /// same order as `fields`.
+    fn build(&self, params: &[String]) -> Result<Matrix<Ratio<i64>>, String> {
+        let size = |i: usize| -> Result<usize, String> {
+            match usize::from_str(params[i].trim()) {
+                Ok(n) if n > 0 => Ok(n),
+                _ => Err(format!("{} must be a positive integer", self.fields()[i]))
+            }
+        };
+        let int = |i: usize| -> Result<i64, String> {
+            i64::from_str(params[i].trim())
+                .map_err(|_| format!("{} must be an integer", self.fields()[i]))
+        };
+        // A bound on the size of the entries, which may be entered negated.
+        let bound = |i: usize| -> Result<i64, String> {
+            int(i)?.checked_abs().ok_or_else(|| format!("{} is too large", self.fields()[i]))
+        };
+        let number = |i: usize| -> Result<Ratio<i64>, String> {
+            eval(&params[i]).map_err(|e| format!("{}: {}", self.fields()[i], e))
+        };
+        let list = |i: usize| -> Result<Vec<Ratio<i64>>, String> {
+            let values = parse_matrix_with(&params[i], eval::<Ratio<i64>>)?;
+            Ok(values.rows().into_iter().flatten().collect())
+        };
+        // Without a seed, a different matrix is generated every time.
+        let rng = |i: usize| -> Result<Rng, String> {
+            if params[i].trim().is_empty() {
+                Ok(Rng::new(time_seed()))
+            } else {
+                u64::from_str(params[i].trim()).map(Rng::new)
+                    .map_err(|_| "Seed must be a nonnegative integer".to_owned())
+            }
+        };
+
+        match *self {
+            Template::Identity => Ok(Matrix::identity(size(0)?)),
+            Template::Zero => Ok(Matrix::zero(size(0)?, size(1)?)),
+            Template::Diagonal => Ok(Matrix::diagonal(&list(0)?)),
+            Template::Hilbert => Ok(Matrix::hilbert(size(0)?)),
+            Template::Vandermonde => Ok(Matrix::vandermonde(&list(0)?)),
+            Template::Tridiagonal => Ok(Matrix::tridiagonal(size(0)?, number(1)?, number(2)?, number(3)?)),
+            Template::RandomIntegers => {
+                let (min, max) = (int(2)?, int(3)?);
+                if min > max {
+                    return Err("Minimum must not be greater than maximum".to_owned());
+                }
+                Ok(Matrix::random_integers(size(0)?, size(1)?, min, max, &mut rng(4)?))
+            },
+            Template::RandomRank =>
+                Matrix::random_with_rank(size(0)?, size(1)?, size(2)?, bound(3)?, &mut rng(4)?),
+            Template::RandomInvertible =>
+                Ok(Matrix::random_with_integer_inverse(size(0)?, bound(1)?, &mut rng(2)?))
+        }
+    }
+}
+
+fn show_template_list(s: &mut Cursive) {
+    let mut list = views::SelectView::new().on_submit(|s, template: &Template| {
+        s.pop_layer();
+        show_template_dialog(s, *template);
+    });
+    for template in Template::all() {
+        list.add_item(template.name(), template);
+    }
+    let diag = views::Dialog::around(list)
+        .title("New from template")
+        .dismiss_button("Cancel");
+    s.add_layer(diag);
+}
+
+/// Asks for the parameters of the template and replaces the setup screen with
+/// the generated matrix.
+fn show_template_dialog(s: &mut Cursive, template: Template) {
+    let fields = template.fields();
+    let mut form = views::ListView::new();
+    for (i, label) in fields.iter().enumerate() {
+        let edit = views::BoxView::with_fixed_width(20, views::EditView::new()
+            .with_id(format!("template_field_{}", i)));
+        form.add_child(label, edit);
+    }
+    let diag = views::Dialog::around(form)
+        .title(template.name())
+        .button("Ok", move |s| {
+            let params: Vec<String> = (0..fields.len())
+                .map(|i| {
+                    s.find_id::<views::EditView>(&format!("template_field_{}", i))
+                        .expect("template field not found")
+                        .get_content()
+                        .to_string()
+                })
+                .collect();
+            match template.build(&params) {
+                Ok(matrix) => {
+                    // Remove this dialog and the setup screen.
+                    s.pop_layer();
+                    s.pop_layer();
+                    show_edit_view(s, matrix);
+                },
+                Err(e) => open_error_popup(s, e)
+            }
+        })
+        .dismiss_button("Cancel");
+    s.add_layer(diag);
+}
+
+/// Shows the matrix for editing, on top of whatever is on the screen.
+pub fn show_edit_view(s: &mut Cursive, data: Matrix<Ratio<i64>>) {
+    add_edit_view(s, new_matrix_view(data));
+    autosave_or_warn(s);
+}
+
+/// Shows the matrix view for editing without saving the session, so that the
+/// caller can finish setting it up first.
+fn add_edit_view(s: &mut Cursive, mview: MatrixView<Ratio<i64>>) {
+    let mview = mview.with_id("matrix_view");
+    let scale_button = views::Button::new("Scale row", scale_action);
+    let swap_button = views::Button::new("Swap rows", swap_action);
+    let add_button = views::Button::new("Add rows", add_action);
+    let layout = views::LinearLayout::vertical()
+        .child(mview)
+        .child(scale_button)
+        .child(swap_button)
+        .child(add_button);
+    let quiz = QUIZ.with(|q| q.borrow().is_some());
+    let diag = views::Dialog::new()
+        .content(layout)
+        .title(if quiz { "Quiz" } else { "MatrixOps" });
+    let diag = if quiz { diag.button("Done", finish_quiz_action) } else { diag };
+    let diag = diag.button("Quit", quit);
+    let eview = CONFIG.with(|c| c.borrow().bindings())
+        .into_iter()
+        .fold(views::OnEventView::new(diag), |view, (action, key)| view.on_event(key, action_callback(action)));
+    s.add_layer(eview);
+    update_status(s);
+}
+
+/// Shows a message in the status bar for a few seconds.
+pub fn show_info<S: Into<String>>(s: &mut Cursive, text: S) {
+    s.call_on_id("status_bar", |bar: &mut StatusBar| bar.info(text));
+    start_fading(s);
+}
+
+/// Shows a problem in the status bar, for when a popup would be in the way.
+pub fn show_error<S: Into<String>>(s: &mut Cursive, text: S) {
+    s.call_on_id("status_bar", |bar: &mut StatusBar| bar.error(text));
+    start_fading(s);
+}
+
+/// Redraws periodically, so that highlighted changes and messages fade out.
+fn start_fading(s: &mut Cursive) {
+    s.set_fps(4);
+}
+
+/// Stops redrawing periodically once nothing is left to fade out.
+fn stop_fading_when_idle(s: &mut Cursive) {
+    let highlighting = s.call_on_id("matrix_view", |view: &mut MatrixView<Ratio<i64>>| view.is_highlighting());
+    let message = s.call_on_id("status_bar", |bar: &mut StatusBar| bar.has_message());
+    if highlighting != Some(true) && message != Some(true) {
+        s.set_fps(0);
+    }
+}
+
+/// Describes the matrix being edited and the quiz being taken in the status
+/// bar.
+fn update_status(s: &mut Cursive) {
+    let mut bar = s.find_id::<StatusBar>("status_bar").expect("status bar not found");
+    s.call_on_id("matrix_view", |view: &mut MatrixView<Ratio<i64>>| bar.update(view));
+    bar.set_quiz(QUIZ.with(|q| q.borrow().as_ref().map(|quiz| (quiz.moves(), quiz.invalid_moves()))));
+}
+
+/// A view of the matrix with the configured colors and notation.
+fn new_matrix_view(matrix: Matrix<Ratio<i64>>) -> MatrixView<Ratio<i64>> {
+    let mut view = MatrixView::new(matrix);
+    CONFIG.with(|c| {
+        let config = c.borrow();
+        view.set_colors(config.colors);
+        if config.notation == Notation::Decimal {
+            let precision = config.precision;
+            view.set_formatter(move |x| decimal(x, precision));
+        }
+    });
+    view
+}
+
+fn action_callback(action: Action) -> fn(&mut Cursive) {
+    match action {
+        Action::Scale => scale_action,
+        Action::Swap => swap_action,
+        Action::Add => add_action,
+        Action::Edit => edit_action,
+        Action::EditRow => edit_row_action,
+        Action::Eliminate => eliminate_action,
+        Action::Normalize => normalize_action,
+        Action::Permute => permute_action,
+        Action::GramSchmidt => gram_schmidt_action,
+        Action::Eigen => eigen_action,
+        Action::Subspaces => subspace_action,
+        Action::ToggleVariables => toggle_variables_action,
+        Action::ToggleDiff => toggle_diff_action,
+        Action::Record => toggle_recording_action,
+        Action::Replay => replay_action,
+        Action::Export => export_action,
+        Action::NewMatrix => new_matrix_action,
+        Action::Help => help_action
+    }
+}
+
+/// Saves the matrix being edited, its history and the view settings, so they
+/// can be restored if the app is closed. Quizzes aren't saved, since one would
+/// be restored as a plain matrix, so the last session from before the quiz is
+/// kept.
+fn autosave(s: &mut Cursive) -> Result<(), String> {
+    if QUIZ.with(|q| q.borrow().is_some()) {
+        return Ok(());
+    }
+    let session = s.call_on_id("matrix_view", |view: &mut MatrixView<Ratio<i64>>| Session {
+        matrix: view.matrix.clone(),
+        history: view.history().to_vec(),
+        column_labels: view.column_labels(),
+        highlight_changes: view.highlight_changes()
+    }).expect("matrix view not found");
+    session.save(&session_path()?)
+}
+
+/// Reports a failed autosave without interrupting the user, and marks the
+/// matrix as unsaved until a later one works.
+fn autosave_or_warn(s: &mut Cursive) {
+    let result = autosave(s);
+    s.call_on_id("status_bar", |bar: &mut StatusBar| bar.set_unsaved(result.is_err()));
+    if let Err(e) = result {
+        show_error(s, format!("Couldn't save the session: {}", e));
+    }
+}
+
+fn restore_session(s: &mut Cursive, session: Session<Ratio<i64>>) {
+    let Session { matrix, history, column_labels, highlight_changes } = session;
+    let mut view = new_matrix_view(matrix);
+    view.set_history(history);
+    view.set_column_labels(column_labels);
+    view.set_highlight_changes(highlight_changes);
+    add_edit_view(s, view);
+    autosave_or_warn(s);
+}
+
+/// Applies the command to the matrix being edited, reporting any error and
+/// updating the status bar. Composite commands show the steps they took.
+fn apply_command(s: &mut Cursive, cmd: Command<Ratio<i64>>) {
+    apply_commands(s, vec![cmd]);
+}
+
+/// Applies the commands in order, either all of them or none if one is an
+/// invalid quiz move or fails. The status bar and the session are updated
+/// once at the end.
+fn apply_commands(s: &mut Cursive, cmds: Vec<Command<Ratio<i64>>>) {
+    // Errors name the step when there is more than one.
+    let describe = |i: usize, cmd: &Command<Ratio<i64>>, e: String| {
+        if cmds.len() == 1 { e } else { format!("step {} ({}): {}", i + 1, cmd, e) }
+    };
+
+    let invalid = QUIZ.with(|q| q.borrow().as_ref().and_then(|quiz| {
+        cmds.iter().enumerate().filter_map(|(i, cmd)| quiz.check_move(cmd).err().map(|e| describe(i, cmd, e))).next()
+    }));
+    if let Some(e) = invalid {
+        QUIZ.with(|q| q.borrow_mut().as_mut().map(|quiz| quiz.record_invalid_move()));
+        update_status(s);
+        return open_error_popup(s, format!("Invalid move: {}", e));
+    }
+
+    let result = s.call_on_id("matrix_view", |view: &mut MatrixView<Ratio<i64>>| -> Result<Vec<String>, String> {
+        // Check every command on a copy first, so that none is applied if
+        // one fails.
+        let mut matrix = view.matrix.clone();
+        for (i, cmd) in cmds.iter().enumerate() {
+            cmd.apply_in_place(&mut matrix, ApplyMode::Strict).map_err(|e| describe(i, cmd, e))?;
+        }
+        let mut notes = Vec::new();
+        for cmd in &cmds {
+            if cmd.is_composite() {
+                let steps: Vec<String> = cmd.expand(&view.matrix)?.iter().map(|step| step.to_string()).collect();
+                let steps = if steps.is_empty() { "nothing to do".to_owned() } else { steps.join(", ") };
+                notes.push(format!("{}: {}", cmd, steps));
+            }
+            view.apply_command(cmd.clone())?;
+        }
+        Ok(notes)
+    }).expect("matrix view not found");
+    let notes = match result {
+        Ok(notes) => notes,
+        Err(e) => return open_error_popup(s, e)
+    };
+    // Only moves that could be applied count.
+    QUIZ.with(|q| {
+        if let Some(ref mut quiz) = *q.borrow_mut() {
+            for _ in &cmds {
+                quiz.record_move();
+            }
+        }
+    });
+    RECORDING.with(|r| {
+        if let Some(ref mut seq) = *r.borrow_mut() {
+            for cmd in &cmds {
+                seq.push(cmd.clone());
+            }
+        }
+    });
+    update_status(s);
+    start_fading(s);
+    if !notes.is_empty() {
+        show_info(s, notes.join(" | "));
+    }
+    autosave_or_warn(s);
+}
+
+thread_local! {
+    /// The commands recorded since recording was started, if it was.
+    static RECORDING: RefCell<Option<CommandSequence<Ratio<i64>>>> = const { RefCell::new(None) };
+}
+
+/// Where recorded sequences are saved.
+fn sequences_path() -> Result<PathBuf, String> {
+    Ok(data_dir()?.join("sequences.txt"))
+}
+
+fn load_sequences() -> Result<Vec<CommandSequence<Ratio<i64>>>, String> {
+    let path = sequences_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let text = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    CommandSequence::parse_all(&text)
+}
+
+fn save_sequence(seq: &CommandSequence<Ratio<i64>>) -> Result<(), String> {
+    let path = sequences_path()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(&path).map_err(|e| e.to_string())?;
+    file.write_all(seq.to_text().as_bytes()).map_err(|e| e.to_string())
+}
+
+/// Starts recording commands, or stops and asks for a name to save the
+/// recording under.
+fn toggle_recording_action(s: &mut Cursive) {
+    let stopped = RECORDING.with(|r| {
+        let mut recording = r.borrow_mut();
+        match recording.take() {
+            Some(seq) => Some(seq),
+            None => {
+                *recording = Some(CommandSequence::new(""));
+                None
+            }
+        }
+    });
+    let recording = stopped.is_none();
+    s.call_on_id("status_bar", |bar: &mut StatusBar| bar.set_recording(recording));
+    let seq = match stopped {
+        Some(seq) => seq,
+        None => {
+            let key = CONFIG.with(|c| c.borrow().key(Action::Record));
+            return show_info(s, format!("Recording; press {} again to stop", key));
+        }
+    };
+    if seq.commands.is_empty() {
+        return show_info(s, "Stopped recording; nothing was recorded");
+    }
+
+    let steps = seq.commands.len();
+    Form::new(format!("Save {} recorded steps", steps))
+        .field_with("Name", |name: &String| {
+            if name.trim().is_empty() { Err("Please enter a name".to_owned()) } else { Ok(()) }
+        })
+        .open(s, move |s, values| {
+            let seq = CommandSequence { name: values.get(0), commands: seq.commands.clone() };
+            match save_sequence(&seq) {
+                Ok(()) => show_info(s, format!("Saved {} ({} steps)", seq.name, steps)),
+                Err(e) => open_error_popup(s, format!("Couldn't save the recording: {}", e))
+            }
+        });
+}
+
+/// Replays a saved sequence on the matrix, optionally on other rows.
+fn replay_action(s: &mut Cursive) {
+    let sequences = match load_sequences() {
+        Ok(sequences) => sequences,
+        Err(e) => return open_error_popup(s, format!("Couldn't load recordings: {}", e))
+    };
+    if sequences.is_empty() {
+        let key = CONFIG.with(|c| c.borrow().key(Action::Record));
+        return open_error_popup(s, format!("Nothing has been recorded yet. Press {} to start recording.", key));
+    }
+
+    let mut list = views::SelectView::new().on_submit(|s, seq: &CommandSequence<Ratio<i64>>| {
+        s.pop_layer();
+        let seq = seq.clone();
+        Form::new(format!("Replay {}", seq.name))
+            .field_with("Row mapping", |map: &String| parse_row_map(map).map(|_| ()))
+            .open(s, move |s, values| {
+                let map = parse_row_map(&values.get::<String>(0)).expect("row mapping was validated");
+                replay(s, &seq, map);
+            });
+    });
+    for seq in sequences {
+        list.add_item(format!("{} ({} steps)", seq.name, seq.commands.len()), seq);
+    }
+    s.add_layer(views::Dialog::around(list)
+        .title("Replay recording")
+        .dismiss_button("Cancel"));
+}
+
+/// Parses a row mapping such as `2 3 1`, which moves row 1 of a recording to
+/// row 2, and so on. An empty mapping leaves the rows alone.
+fn parse_row_map(text: &str) -> Result<Option<Vec<usize>>, String> {
+    if text.trim().is_empty() {
+        Ok(None)
+    } else {
+        parse_rows(text).map(Some)
+    }
+}
+
+/// Parses a list of rows separated by spaces or commas.
+fn parse_rows(text: &str) -> Result<Vec<usize>, String> {
+    text.split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|s| !s.is_empty())
+        .map(|s| usize::from_str(s).map_err(|_| format!("{} is not a row", s)))
+        .collect()
+}
+
+fn replay(s: &mut Cursive, seq: &CommandSequence<Ratio<i64>>, map: Option<Vec<usize>>) {
+    let seq = match map {
+        Some(map) => match seq.remap_rows(&map) {
+            Ok(seq) => seq,
+            Err(e) => return open_error_popup(s, e)
+        },
+        None => seq.clone()
+    };
+    apply_commands(s, seq.commands);
+}
+
+/// Goes back to the setup screen to enter another matrix.
+fn new_matrix_action(s: &mut Cursive) {
+    abandon_quiz(s);
+    s.pop_layer();
+    s.call_on_id("status_bar", |bar: &mut StatusBar| bar.clear());
+    show_setup_view(s);
+}
+
+/// Switches the column labels between column numbers and the variables of a
+/// linear system.
+fn toggle_variables_action(s: &mut Cursive) {
+    s.call_on_id("matrix_view", |view: &mut MatrixView<Ratio<i64>>| {
+        let labels = match view.column_labels() {
+            ColumnLabels::Numbered => ColumnLabels::Variables,
+            ColumnLabels::Variables => ColumnLabels::Numbered
+        };
+        view.set_column_labels(labels);
+    });
+    autosave_or_warn(s);
+}
+
+/// Switches highlighting of the cells changed by each command on or off.
+fn toggle_diff_action(s: &mut Cursive) {
+    s.call_on_id("matrix_view", |view: &mut MatrixView<Ratio<i64>>| {
+        let enabled = view.highlight_changes();
+        view.set_highlight_changes(!enabled);
+    });
+    autosave_or_warn(s);
+}
+
+/// The number of rows and columns of the matrix being edited.
+fn matrix_size(s: &mut Cursive) -> (usize, usize) {
+    let mview = s.find_id::<MatrixView<Ratio<i64>>>("matrix_view")
+        .expect("matrix view not found");
+    (mview.matrix.rows, mview.matrix.cols)
+}
+
+/// Checks that a (1-based) row or column index is at most `max`.
+fn index_in(max: usize) -> impl Fn(&usize) -> Result<(), String> {
+    move |&i| if i >= 1 && i <= max {
+        Ok(())
+    } else {
+        Err(format!("Must be between 1 and {}", max))
+    }
+}
+
+fn scale_action(s: &mut Cursive) {
+    let (rows, _) = matrix_size(s);
+    Form::new("Scale row")
+        .field_with("Row", index_in(rows))
+        .number::<Ratio<i64>>("Scale by")
+        .open(s, |s, values| {
+            apply_command(s, Command::ScaleRow {
+                row: values.get(0),
+                coeff: values.get(1)
+            });
+        });
+}
+
+fn swap_action(s: &mut Cursive) {
+    let (rows, _) = matrix_size(s);
+    Form::new("Swap rows")
+        .field_with("First row", index_in(rows))
+        .field_with("Second row", index_in(rows))
+        .open(s, |s, values| {
+            apply_command(s, Command::SwapRow {
+                row1: values.get(0),
+                row2: values.get(1)
+            });
+        });
+}
+
+fn edit_action(s: &mut Cursive) {
+    let (rows, cols) = matrix_size(s);
+    Form::new("Edit cell")
+        .field_with("Row", index_in(rows))
+        .field_with("Column", index_in(cols))
+        .number::<Ratio<i64>>("New value")
+        .open(s, |s, values| {
+            apply_command(s, Command::EditCell {
+                row: values.get(0),
+                col: values.get(1),
+                value: values.get(2)
+            });
+        });
+}
+
+fn edit_row_action(s: &mut Cursive) {
+    let (rows, _) = matrix_size(s);
+    Form::new("Edit row")
+        .field_with("Row", index_in(rows))
+        .open(s, |s, values| {
+            let row: usize = values.get(0);
+            let current = s.find_id::<MatrixView<Ratio<i64>>>("matrix_view")
+                .expect("matrix view not found")
+                .matrix
+                .get_row(row)
+                .unwrap();
+
+            // Every cell of the row is edited at once, starting from its
+            // current value.
+            let form = current.iter()
+                .enumerate()
+                .fold(Form::new(format!("Edit row {}", row)), |form, (c, value)| {
+                    form.number::<Ratio<i64>>(&format!("C{}", c + 1)).initial(value.to_string())
+                });
+            let cols = current.len();
+            form.open(s, move |s, values| {
+                apply_command(s, Command::EditRow {
+                    row,
+                    values: (0..cols).map(|c| values.get(c)).collect()
+                });
+            });
+        });
+}
+
+fn add_action(s: &mut Cursive) {
+    let (rows, _) = matrix_size(s);
+    Form::new("Add rows")
+        .field_with("Source row", index_in(rows))
+        .number::<Ratio<i64>>("Multiplied by")
+        .field_with("Destination row", index_in(rows))
+        .open(s, |s, values| {
+            apply_command(s, Command::AddRow {
+                src: values.get(0),
+                coeff: values.get(1),
+                dest: values.get(2)
+            });
+        });
+}
+
+fn eliminate_action(s: &mut Cursive) {
+    let (rows, cols) = matrix_size(s);
+    Form::new("Clear column")
+        .field_with("Pivot row", index_in(rows))
+        .field_with("Column", index_in(cols))
+        .open(s, |s, values| {
+            apply_command(s, Command::EliminateColumn {
+                pivot_row: values.get(0),
+                col: values.get(1)
+            });
+        });
+}
+
+fn normalize_action(s: &mut Cursive) {
+    let (rows, _) = matrix_size(s);
+    Form::new("Make the leading entry 1")
+        .field_with("Row", index_in(rows))
+        .open(s, |s, values| {
+            apply_command(s, Command::NormalizePivot { row: values.get(0) });
+        });
+}
+
+fn permute_action(s: &mut Cursive) {
+    let (rows, _) = matrix_size(s);
+    let initial: Vec<String> = (1..(rows + 1)).map(|r| r.to_string()).collect();
+    Form::new("Reorder rows")
+        .field_with("New order", |order: &String| parse_rows(order).map(|_| ()))
+        .initial(initial.join(" "))
+        .open(s, |s, values| {
+            let order = parse_rows(&values.get::<String>(0)).expect("order was validated");
+            apply_command(s, Command::PermuteRows(order));
+        });
+}
+
+/// Shows the matrix as text in the configured format, with buttons to switch
+/// to the others and to add or remove the subspaces.
+fn export_action(s: &mut Cursive) {
+    let matrix = s.find_id::<MatrixView<Ratio<i64>>>("matrix_view")
+        .expect("matrix view not found")
+        .matrix
+        .clone();
+    let (format, subspaces) = CONFIG.with(|c| {
+        let config = c.borrow();
+        (config.export_format, config.export_subspaces)
+    });
+    // The buttons share the format and whether the subspaces are shown.
+    let state = Rc::new(Cell::new((format, subspaces)));
+
+    let text = views::TextView::new(export_text(&matrix, format, subspaces)).with_id("export_text");
+    let diag = ExportFormat::all().into_iter().fold(views::Dialog::around(text).title("Export"), |diag, format| {
+        let matrix = matrix.clone();
+        let state = state.clone();
+        diag.button(format.name(), move |s| {
+            let (_, subspaces) = state.get();
+            state.set((format, subspaces));
+            show_export(s, &matrix, format, subspaces);
+        })
+    });
+    let diag = diag.button("subspaces", move |s| {
+        let (format, subspaces) = state.get();
+        state.set((format, !subspaces));
+        show_export(s, &matrix, format, !subspaces);
+    });
+    s.add_layer(diag.dismiss_button("Close"));
+}
+
+fn show_export(s: &mut Cursive, matrix: &Matrix<Ratio<i64>>, format: ExportFormat, subspaces: bool) {
+    let text = export_text(matrix, format, subspaces);
+    s.call_on_id("export_text", |view: &mut views::TextView| view.set_content(text));
+}
+
+fn export_text(matrix: &Matrix<Ratio<i64>>, format: ExportFormat, subspaces: bool) -> String {
+    if subspaces {
+        export_with_subspaces(matrix, format).unwrap_or_else(|e| format!("Error: {}", e))
+    } else {
+        export(matrix, format)
+    }
+}
+
+fn gram_schmidt_action(s: &mut Cursive) {
+    let matrix = s.find_id::<MatrixView<Ratio<i64>>>("matrix_view")
+        .expect("matrix view not found")
+        .matrix
+        .clone();
+    match matrix.orthogonalize() {
+        Ok((_, steps)) => show_projection_steps(s, matrix, steps),
+        Err(e) => open_error_popup(s, e)
+    }
+}
+
+/// Shows the columns of the matrix and applies one Gram-Schmidt projection
+/// each time "Next" is pressed.
+fn show_projection_steps(s: &mut Cursive, matrix: Matrix<Ratio<i64>>,
+                         steps: Vec<Projection<Ratio<i64>>>) {
+    let intro = if steps.is_empty() {
+        "The columns are already orthogonal.".to_owned()
+    } else {
+        format!("{} projections to apply.", steps.len())
+    };
+    let mview = new_matrix_view(matrix).with_id("gram_schmidt_view");
+    let step_text = views::TextView::new(intro).with_id("gram_schmidt_step");
+    let layout = views::LinearLayout::vertical()
+        .child(mview)
+        .child(views::DummyView {})
+        .child(step_text);
+
+    let next = Cell::new(0);
+    let diag = views::Dialog::around(layout)
+        .title("Gram-Schmidt")
+        .button("Next", move |s| {
+            let index = next.get();
+            if let Some(step) = steps.get(index) {
+                s.call_on_id("gram_schmidt_view", |view: &mut MatrixView<Ratio<i64>>| {
+                    if let Ok(upd) = step.apply(&view.matrix) {
+                        view.matrix = upd;
+                    }
+                });
+                let text = if index + 1 == steps.len() {
+                    format!("Step {}/{}: {}\nThe columns are now orthogonal.",
+                            index + 1, steps.len(), step)
+                } else {
+                    format!("Step {}/{}: {}", index + 1, steps.len(), step)
+                };
+                s.call_on_id("gram_schmidt_step", |view: &mut views::TextView| {
+                    view.set_content(text);
+                });
+                next.set(index + 1);
+            }
+        })
+        .dismiss_button("Close");
+    s.add_layer(diag);
+}
+
+fn eigen_action(s: &mut Cursive) {
+    let matrix = s.find_id::<MatrixView<Ratio<i64>>>("matrix_view")
+        .expect("matrix view not found")
+        .matrix
+        .clone();
+    match eigen_report(&matrix) {
+        Ok(report) => {
+            let diag = views::Dialog::around(views::TextView::new(report).with_id("eigen_report"))
+                .title("Eigen")
+                .dismiss_button("Close");
+            s.add_layer(diag);
+        },
+        Err(e) => open_error_popup(s, e)
+    }
+}
+
+/// Describes the characteristic polynomial, the exact rational eigenvalues
+/// with their eigenspaces, and approximations of every eigenvalue.
+fn eigen_report(matrix: &Matrix<Ratio<i64>>) -> Result<String, String> {
+    let poly = matrix.characteristic_polynomial()?;
+    let mut report = format!("Characteristic polynomial: {}\n", poly);
+
+    let spaces = matrix.rational_eigenspaces()?;
+    if !spaces.is_empty() {
+        report.push_str("\nRational eigenvalues:\n");
+    }
+    for space in spaces {
+        report.push_str(&format!("  \u{3bb} = {} (multiplicity {})\n", space.value, space.multiplicity));
+        for v in space.basis {
+            report.push_str(&format!("    {}\n", format_vector(&v)));
+        }
+    }
+
+    let approx = matrix.map(|x| *x.numer() as f64 / *x.denom() as f64);
+    report.push_str("\nAll eigenvalues (approximate):\n");
+    for v in approx.eigenvalues()? {
+        if is_real(&v) {
+            report.push_str(&format!("  {:.6}\n", v.re));
+        } else {
+            let sign = if v.im < 0.0 { '-' } else { '+' };
+            report.push_str(&format!("  {:.6} {} {:.6}i\n", v.re, sign, v.im.abs()));
+        }
+    }
+    Ok(report)
+}
+
+fn subspace_action(s: &mut Cursive) {
+    let matrix = s.find_id::<MatrixView<Ratio<i64>>>("matrix_view")
+        .expect("matrix view not found")
+        .matrix
+        .clone();
+    match subspace_report(&matrix) {
+        Ok(report) => {
+            let diag = views::Dialog::around(views::TextView::new(report).with_id("subspace_report"))
+                .title("Subspaces")
+                .dismiss_button("Close");
+            s.add_layer(diag);
+        },
+        Err(e) => open_error_popup(s, e)
+    }
+}
+
+/// Describes bases of the four fundamental subspaces of the matrix.
+fn subspace_report(matrix: &Matrix<Ratio<i64>>) -> Result<String, String> {
+    let pivots: Vec<String> = matrix.pivot_columns()?.iter().map(|c| c.to_string()).collect();
+    let mut report = format!("Pivot columns: {}\n", pivots.join(", "));
+
+    let spaces = vec![
+        ("Column space", matrix.column_space()?),
+        ("Row space", matrix.row_space()?),
+        ("Null space", matrix.null_space()?),
+        ("Left null space", matrix.left_null_space()?)
+    ];
+    for (name, basis) in spaces {
+        report.push_str(&format!("\n{} (dimension {}):\n", name, basis.len()));
+        if basis.is_empty() {
+            report.push_str("  {0}\n");
+        }
+        for v in basis {
+            report.push_str(&format!("  {}\n", format_vector(&v)));
+        }
+    }
+    Ok(report)
+}
+
+fn format_vector<T: ToString>(v: &[T]) -> String {
+    let entries: Vec<String> = v.iter().map(|x| x.to_string()).collect();
+    format!("({})", entries.join(", "))
+}
@@ -1,5 +1,7 @@
-use std::ops::{Add, Mul};
-use matrix::{Matrix, MatrixResult};
+use std::fmt;
+use std::str::FromStr;
+use num_traits::Zero;
+use matrix::{Field, Matrix, MatrixResult};
 
 #[derive(PartialEq, Clone, Debug)]
 pub enum Command<T> {
@@ -7,39 +9,376 @@ pub enum Command<T> {
     ScaleRow { coeff: T, row: usize },
     SwapRow { row1: usize, row2: usize },
     EditCell { row: usize, col: usize, value: T },
-    EditRow { row: usize, values: Vec<T> }
+    EditRow { row: usize, values: Vec<T> },
+    /// Clears every other entry of the column by adding multiples of the
+    /// pivot row.
+    EliminateColumn { pivot_row: usize, col: usize },
+    /// Scales the row so that its leading entry is 1.
+    NormalizePivot { row: usize },
+    /// Reorders the rows, so that row `i` becomes the row that was at
+    /// position `order[i - 1]`.
+    PermuteRows(Vec<usize>)
+}
+
+/// Whether `Command::apply_with` accepts row operations that can't be undone.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ApplyMode {
+    /// Scaling a row by zero and adding a row to itself are errors.
+    Strict,
+    /// Every command is applied.
+    Permissive
 }
 
 impl <T> Command<T> {
-    pub fn apply(&self, matrix: &Matrix<T>) -> MatrixResult<Matrix<T>>
-        where T: Clone + Add<T, Output = T> + Mul<T, Output = T> {
+    /// Applies the command in permissive mode.
+    pub fn apply(&self, matrix: &Matrix<T>) -> MatrixResult<Matrix<T>> where T: Field {
+        self.apply_with(matrix, ApplyMode::Permissive)
+    }
+
+    pub fn apply_with(&self, matrix: &Matrix<T>, mode: ApplyMode) -> MatrixResult<Matrix<T>>
+        where T: Field {
         let mut copy = matrix.clone();
-        let _: () = match self {
-            &Command::AddRow { ref coeff, src, dest } => {
-                let src_row = matrix.get_row(src);
-                let dest_row = matrix.get_row(dest);
-                let new_dest: Vec<T> = src_row.iter()
-                    .zip(dest_row)
-                    .map(|(s, d)| coeff.clone() * s.clone() + d)
-                    .collect();
-                copy.set_row(dest, &new_dest)?;
+        self.apply_in_place(&mut copy, mode)?;
+        Ok(copy)
+    }
+
+    /// Applies the command to the matrix without copying it. If the command
+    /// fails, the matrix is left unchanged.
+    pub fn apply_in_place(&self, matrix: &mut Matrix<T>, mode: ApplyMode) -> MatrixResult<()>
+        where T: Field {
+        if mode == ApplyMode::Strict {
+            self.check_invertible()?;
+        }
+        match *self {
+            Command::AddRow { ref coeff, src, dest } => matrix.add_scaled_row(src, coeff, dest),
+            Command::ScaleRow { ref coeff, row } => matrix.row_foreach(row, |x| coeff.clone() * x.clone()),
+            Command::SwapRow { row1, row2 } => matrix.swap_rows(row1, row2),
+            Command::EditCell { row, col, ref value } => matrix.set(row, col, value.clone()),
+            Command::EditRow { row, ref values } => matrix.set_row(row, values),
+            Command::EliminateColumn { .. } | Command::NormalizePivot { .. } | Command::PermuteRows(_) => {
+                // Expanding checks the command against the matrix, so the
+                // steps can't fail part way through.
+                for step in self.expand(matrix)? {
+                    step.apply_in_place(matrix, mode)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Whether the command is made up of several row operations.
+    pub fn is_composite(&self) -> bool {
+        matches!(*self, Command::EliminateColumn { .. } | Command::NormalizePivot { .. } | Command::PermuteRows(_))
+    }
+
+    /// Breaks a composite command into the `AddRow`, `ScaleRow` and `SwapRow`
+    /// steps that it takes on the matrix. Other commands are returned as is.
+    pub fn expand(&self, matrix: &Matrix<T>) -> MatrixResult<Vec<Command<T>>> where T: Field {
+        match *self {
+            Command::EliminateColumn { pivot_row, col } => {
+                let pivot = matrix.get(pivot_row, col)
+                    .ok_or_else(|| format!("({}, {}) is out of bounds", pivot_row, col))?;
+                if pivot.is_zero() {
+                    return Err(format!("the pivot ({}, {}) is zero", pivot_row, col));
+                }
+                Ok((1..(matrix.rows + 1))
+                    .filter(|&r| r != pivot_row)
+                    .filter_map(|r| {
+                        let value = matrix.get(r, col).unwrap();
+                        if value.is_zero() {
+                            None
+                        } else {
+                            Some(Command::AddRow { coeff: -(value / pivot.clone()), src: pivot_row, dest: r })
+                        }
+                    })
+                    .collect())
             },
-            &Command::ScaleRow { ref coeff, row } => {
-                copy.row_foreach(row, |x| coeff.clone() * x.clone())?;
+            Command::NormalizePivot { row } => {
+                let lead = matrix.row(row)
+                    .ok_or_else(|| format!("row {} is out of bounds", row))?
+                    .iter()
+                    .find(|x| !x.is_zero())
+                    .cloned()
+                    .ok_or_else(|| format!("row {} has no pivot", row))?;
+                if lead == T::one() {
+                    Ok(Vec::new())
+                } else {
+                    Ok(vec![Command::ScaleRow { coeff: T::one() / lead, row }])
+                }
             },
-            &Command::SwapRow { row1, row2 } => {
-                let r1 = matrix.get_row(row1);
-                let r2 = matrix.get_row(row2);
-                copy.set_row(row1, &r2)?;
-                copy.set_row(row2, &r1)?;
+            Command::PermuteRows(ref order) => {
+                let mut sorted = order.clone();
+                sorted.sort();
+                if sorted != (1..(matrix.rows + 1)).collect::<Vec<usize>>() {
+                    return Err(format!("the order must contain each of the rows 1 to {} once", matrix.rows));
+                }
+                // current[i] is the original row now at position i + 1.
+                let mut current: Vec<usize> = (1..(matrix.rows + 1)).collect();
+                let mut steps = Vec::new();
+                for i in 0..order.len() {
+                    if current[i] != order[i] {
+                        let j = current.iter().position(|&r| r == order[i]).unwrap();
+                        current.swap(i, j);
+                        steps.push(Command::SwapRow { row1: i + 1, row2: j + 1 });
+                    }
+                }
+                Ok(steps)
             },
-            &Command::EditCell { row, col, ref value } => {
-                copy.set(row, col, value.clone())?;
+            _ => Ok(vec![self.clone()])
+        }
+    }
+}
+
+impl <T> Command<T> {
+    /// Checks that the command isn't a row operation that loses information,
+    /// which are scaling a row by zero and adding a row to itself.
+    pub fn check_invertible(&self) -> MatrixResult<()> where T: Zero {
+        match *self {
+            Command::ScaleRow { ref coeff, .. } if coeff.is_zero() =>
+                Err("scaling a row by zero can't be undone".to_owned()),
+            Command::AddRow { src, dest, .. } if src == dest =>
+                Err(format!("can't add row {} to itself", src)),
+            _ => Ok(())
+        }
+    }
+
+    /// The (1-based) rows that the command changes. Which rows
+    /// `EliminateColumn` changes depends on the matrix, so none are given;
+    /// expand it to find them.
+    pub fn affected_rows(&self) -> Vec<usize> {
+        match *self {
+            Command::AddRow { dest, .. } => vec![dest],
+            Command::ScaleRow { row, .. } => vec![row],
+            Command::SwapRow { row1, row2 } => vec![row1, row2],
+            Command::EditCell { row, .. } => vec![row],
+            Command::EditRow { row, .. } => vec![row],
+            Command::EliminateColumn { .. } => Vec::new(),
+            Command::NormalizePivot { row } => vec![row],
+            Command::PermuteRows(ref order) => (1..(order.len() + 1)).filter(|&r| order[r - 1] != r).collect()
+        }
+    }
+}
+
+impl <T: fmt::Display> fmt::Display for Command<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Command::AddRow { ref coeff, src, dest } =>
+                write!(f, "R{} += ({})R{}", dest, coeff, src),
+            Command::ScaleRow { ref coeff, row } =>
+                write!(f, "R{} *= {}", row, coeff),
+            Command::SwapRow { row1, row2 } =>
+                write!(f, "R{} <-> R{}", row1, row2),
+            Command::EditCell { row, col, ref value } =>
+                write!(f, "({}, {}) = {}", row, col, value),
+            Command::EditRow { row, ref values } => {
+                let entries: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+                write!(f, "R{} = ({})", row, entries.join(", "))
             },
-            &Command::EditRow { row, ref values } => {
-                copy.set_row(row, &*values)?;
+            Command::EliminateColumn { pivot_row, col } =>
+                write!(f, "clear C{} using R{}", col, pivot_row),
+            Command::NormalizePivot { row } =>
+                write!(f, "normalize R{}", row),
+            Command::PermuteRows(ref order) => {
+                let rows: Vec<String> = order.iter().map(|r| format!("R{}", r)).collect();
+                write!(f, "reorder ({})", rows.join(", "))
             }
+        }
+    }
+}
+
+impl <T: Clone> Command<T> {
+    /// Moves the command to other rows: row `r` becomes row `map[r - 1]`.
+    pub fn remap_rows(&self, map: &[usize]) -> MatrixResult<Command<T>> {
+        let row = |r: usize| match map.get(r.wrapping_sub(1)) {
+            Some(&new) => Ok(new),
+            None => Err(format!("row {} is not in the row mapping", r))
         };
-        Ok(copy)
+        Ok(match *self {
+            Command::AddRow { ref coeff, src, dest } =>
+                Command::AddRow { coeff: coeff.clone(), src: row(src)?, dest: row(dest)? },
+            Command::ScaleRow { ref coeff, row: r } =>
+                Command::ScaleRow { coeff: coeff.clone(), row: row(r)? },
+            Command::SwapRow { row1, row2 } =>
+                Command::SwapRow { row1: row(row1)?, row2: row(row2)? },
+            Command::EditCell { row: r, col, ref value } =>
+                Command::EditCell { row: row(r)?, col, value: value.clone() },
+            Command::EditRow { row: r, ref values } =>
+                Command::EditRow { row: row(r)?, values: values.clone() },
+            Command::EliminateColumn { pivot_row, col } =>
+                Command::EliminateColumn { pivot_row: row(pivot_row)?, col },
+            Command::NormalizePivot { row: r } =>
+                Command::NormalizePivot { row: row(r)? },
+            Command::PermuteRows(ref order) => {
+                // Where row i got row order[i], row map(i) now gets row
+                // map(order[i]).
+                let mut remapped = vec![0; order.len()];
+                for (i, &r) in order.iter().enumerate() {
+                    match remapped.get_mut(row(i + 1)?.wrapping_sub(1)) {
+                        Some(slot) => *slot = row(r)?,
+                        None => return Err("can't move a reordering outside of its rows".to_owned())
+                    }
+                }
+                Command::PermuteRows(remapped)
+            }
+        })
+    }
+}
+
+/// Parses a command in the format that `Display` writes.
+impl <T: FromStr> FromStr for Command<T> {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Command<T>, String> {
+        let text = text.trim();
+        let invalid = || format!("invalid command: {}", text);
+        let index = |s: &str| usize::from_str(s.trim()).map_err(|_| invalid());
+        let value = |s: &str| T::from_str(s.trim()).map_err(|_| invalid());
+
+        let row_label = |s: &str| match s.trim().strip_prefix('R') {
+            Some(r) => index(r),
+            None => Err(invalid())
+        };
+        if let Some(rest) = text.strip_prefix("clear C") {
+            let mut parts = rest.splitn(2, " using ");
+            return match (parts.next(), parts.next()) {
+                (Some(col), Some(row)) => Ok(Command::EliminateColumn { pivot_row: row_label(row)?, col: index(col)? }),
+                _ => Err(invalid())
+            };
+        }
+        if let Some(row) = text.strip_prefix("normalize ") {
+            return Ok(Command::NormalizePivot { row: row_label(row)? });
+        }
+        if let Some(rows) = text.strip_prefix("reorder (").and_then(|t| t.strip_suffix(')')) {
+            let order = rows
+                .split(',')
+                .map(row_label)
+                .collect::<Result<Vec<usize>, String>>()?;
+            return Ok(Command::PermuteRows(order));
+        }
+
+        if text.starts_with('(') {
+            let close = text.find(')').ok_or_else(invalid)?;
+            let mut coords = text[1..close].split(',');
+            let (row, col) = match (coords.next(), coords.next(), coords.next()) {
+                (Some(r), Some(c), None) => (index(r)?, index(c)?),
+                _ => return Err(invalid())
+            };
+            let rest = text[(close + 1)..].trim_start();
+            if !rest.starts_with('=') {
+                return Err(invalid());
+            }
+            return Ok(Command::EditCell { row, col, value: value(&rest[1..])? });
+        }
+
+        if !text.starts_with('R') {
+            return Err(invalid());
+        }
+        let digits = text[1..].find(|c: char| !c.is_ascii_digit()).map(|i| i + 1).ok_or_else(invalid)?;
+        let row = index(&text[1..digits])?;
+        let rest = text[digits..].trim_start();
+
+        if let Some(rest) = rest.strip_prefix("+=") {
+            // (coeff)Rsrc
+            let rest = rest.trim();
+            let split = rest.rfind(")R").ok_or_else(invalid)?;
+            if !rest.starts_with('(') {
+                return Err(invalid());
+            }
+            Ok(Command::AddRow {
+                coeff: value(&rest[1..split])?,
+                src: index(&rest[(split + 2)..])?,
+                dest: row
+            })
+        } else if let Some(coeff) = rest.strip_prefix("*=") {
+            Ok(Command::ScaleRow { coeff: value(coeff)?, row })
+        } else if let Some(other) = rest.strip_prefix("<->") {
+            Ok(Command::SwapRow { row1: row, row2: row_label(other)? })
+        } else if let Some(values) = rest.strip_prefix('=') {
+            let values = values.trim();
+            if !(values.starts_with('(') && values.ends_with(')')) {
+                return Err(invalid());
+            }
+            let values = values[1..(values.len() - 1)]
+                .split(',')
+                .map(value)
+                .collect::<Result<Vec<T>, String>>()?;
+            Ok(Command::EditRow { row, values })
+        } else {
+            Err(invalid())
+        }
+    }
+}
+
+/// A named list of commands, such as a recorded elimination pattern, that can
+/// be replayed on other matrices.
+#[derive(PartialEq, Clone, Debug)]
+pub struct CommandSequence<T> {
+    pub name: String,
+    pub commands: Vec<Command<T>>
+}
+
+impl <T> CommandSequence<T> {
+
+    pub fn new<S: Into<String>>(name: S) -> CommandSequence<T> {
+        CommandSequence {
+            name: name.into(),
+            commands: Vec::new()
+        }
+    }
+
+    pub fn push(&mut self, cmd: Command<T>) {
+        self.commands.push(cmd);
+    }
+
+    /// Applies every command in order, in strict mode.
+    pub fn apply(&self, matrix: &Matrix<T>) -> MatrixResult<Matrix<T>>
+        where T: Field + fmt::Display {
+        let mut result = matrix.clone();
+        for (i, cmd) in self.commands.iter().enumerate() {
+            cmd.apply_in_place(&mut result, ApplyMode::Strict)
+                .map_err(|e| format!("step {} ({}): {}", i + 1, cmd, e))?;
+        }
+        Ok(result)
+    }
+
+    /// The same sequence operating on other rows: row `r` becomes row
+    /// `map[r - 1]`.
+    pub fn remap_rows(&self, map: &[usize]) -> MatrixResult<CommandSequence<T>> where T: Clone {
+        Ok(CommandSequence {
+            name: self.name.clone(),
+            commands: self.commands.iter().map(|cmd| cmd.remap_rows(map)).collect::<MatrixResult<_>>()?
+        })
+    }
+
+    /// Writes the sequence as a `name:` line followed by one command per
+    /// line.
+    pub fn to_text(&self) -> String where T: fmt::Display {
+        let mut text = format!("name: {}\n", self.name);
+        for cmd in &self.commands {
+            text.push_str(&format!("{}\n", cmd));
+        }
+        text
+    }
+
+    /// Reads every sequence written by `to_text` from the text. Blank lines
+    /// and lines starting with `#` are ignored.
+    pub fn parse_all(text: &str) -> MatrixResult<Vec<CommandSequence<T>>> where T: FromStr {
+        let mut sequences: Vec<CommandSequence<T>> = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix("name:") {
+                sequences.push(CommandSequence::new(name.trim()));
+                continue;
+            }
+            let cmd = Command::from_str(line).map_err(|e| format!("line {}: {}", i + 1, e))?;
+            match sequences.last_mut() {
+                Some(seq) => seq.push(cmd),
+                None => return Err(format!("line {}: expected a name: line first", i + 1))
+            }
+        }
+        Ok(sequences)
     }
 }
@@ -0,0 +1,194 @@
+use cursive::Cursive;
+use cursive::traits::Identifiable;
+use cursive::view::{Offset, Position};
+use cursive::views;
+use expr::{eval, Scalar};
+use std::fmt::Display;
+use std::rc::Rc;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Each open form gets its own prefix for the ids of its views.
+static NEXT_FORM: AtomicUsize = AtomicUsize::new(0);
+
+/// A dialog that asks for several values at once. Each field is parsed with
+/// `FromStr`, or evaluated as an arithmetic expression for number fields, and
+/// checked as it is typed. Any problem, or the value of an expression, is
+/// shown under the field, and the form is only submitted once every field is
+/// valid.
+///
+/// Example:
+/// ```no_run
+/// # extern crate cursive;
+/// # extern crate matrixops;
+/// # fn main() {
+/// use cursive::Cursive;
+/// use cursive::views::Dialog;
+/// use matrixops::ui::Form;
+///
+/// let s: &mut Cursive = &mut Cursive::new();
+/// Form::new("Two numbers")
+///     .field::<usize>("a")
+///     .field_with("b", |b: &usize| if *b > 0 { Ok(()) } else { Err("b can't be 0".to_owned()) })
+///     .number::<f64>("c")
+///     .open(s, |s, values| {
+///         let (a, b, c): (usize, usize, f64) = (values.get(0), values.get(1), values.get(2));
+///         s.add_layer(Dialog::text(format!("a / b * c = {}", (a / b) as f64 * c)).dismiss_button("Close"));
+///     });
+/// # }
+/// ```
+pub struct Form {
+    title: String,
+    fields: Vec<FormField>
+}
+
+/// Parses and validates the text of a field, returning it in the form that
+/// `FormValues::get` parses.
+type Check = Rc<dyn Fn(&str) -> Result<String, String>>;
+
+struct FormField {
+    label: String,
+    initial: String,
+    check: Check,
+    /// Whether to show what the text evaluates to.
+    preview: bool
+}
+
+impl Form {
+
+    pub fn new<S: Into<String>>(title: S) -> Form {
+        Form {
+            title: title.into(),
+            fields: Vec::new()
+        }
+    }
+
+    /// Adds a field whose text must parse as a `T`.
+    pub fn field<T: 'static + FromStr>(self, label: &str) -> Form {
+        self.field_with(label, |_: &T| Ok(()))
+    }
+
+    /// Adds a field whose text must parse as a `T` that passes `validate`.
+    pub fn field_with<T, F>(mut self, label: &str, validate: F) -> Form
+        where T: 'static + FromStr, F: 'static + Fn(&T) -> Result<(), String> {
+        let check = move |text: &str| match T::from_str(text.trim()) {
+            Ok(value) => validate(&value).map(|_| text.trim().to_owned()),
+            Err(_) => Err("Please enter a number".to_owned())
+        };
+        self.fields.push(FormField {
+            label: label.to_owned(),
+            initial: String::new(),
+            check: Rc::new(check),
+            preview: false
+        });
+        self
+    }
+
+    /// Adds a field that is evaluated as an arithmetic expression, such as
+    /// `1/2 + 1/3`, and shows its value while it is typed.
+    pub fn number<T>(self, label: &str) -> Form where T: 'static + Scalar + Display + FromStr {
+        self.number_with(label, |_: &T| Ok(()))
+    }
+
+    /// Adds a number field whose value must pass `validate`.
+    pub fn number_with<T, F>(mut self, label: &str, validate: F) -> Form
+        where T: 'static + Scalar + Display + FromStr, F: 'static + Fn(&T) -> Result<(), String> {
+        let check = move |text: &str| {
+            let value = eval::<T>(text)?;
+            validate(&value)?;
+            Ok(value.to_string())
+        };
+        self.fields.push(FormField {
+            label: label.to_owned(),
+            initial: String::new(),
+            check: Rc::new(check),
+            preview: true
+        });
+        self
+    }
+
+    /// Sets the text that the last added field starts with.
+    pub fn initial<S: Into<String>>(mut self, text: S) -> Form {
+        if let Some(field) = self.fields.last_mut() {
+            field.initial = text.into();
+        }
+        self
+    }
+
+    /// Shows the form. Once it is submitted with valid values, it is closed
+    /// and `on_submit` is called with the values.
+    pub fn open<F>(self, s: &mut Cursive, on_submit: F)
+        where F: 'static + Fn(&mut Cursive, &FormValues) {
+        let prefix = format!("form{}", NEXT_FORM.fetch_add(1, Ordering::Relaxed));
+        let ids: Vec<(String, String)> = (0..self.fields.len())
+            .map(|i| (format!("{}_field{}", prefix, i), format!("{}_note{}", prefix, i)))
+            .collect();
+        let checks: Vec<_> = self.fields.iter().map(|f| f.check.clone()).collect();
+
+        let submit_ids = ids.clone();
+        let submit = Rc::new(move |s: &mut Cursive| {
+            let mut values = Vec::with_capacity(checks.len());
+            let mut valid = true;
+            for (check, (field_id, note_id)) in checks.iter().zip(&submit_ids) {
+                let text = s.find_id::<views::EditView>(field_id)
+                    .expect("form field not found")
+                    .get_content()
+                    .to_string();
+                match check(&text) {
+                    Ok(value) => values.push(value),
+                    Err(e) => {
+                        valid = false;
+                        s.call_on_id(note_id, |view: &mut views::TextView| view.set_content(e));
+                    }
+                }
+            }
+            if valid {
+                s.pop_layer();
+                on_submit(s, &FormValues(values));
+            }
+        });
+
+        let mut list = views::ListView::new();
+        for (field, (field_id, note_id)) in self.fields.into_iter().zip(ids) {
+            let check = field.check;
+            let preview = field.preview;
+            let edit_note_id = note_id.clone();
+            let submit = submit.clone();
+            let edit = views::EditView::new()
+                .content(field.initial)
+                .on_edit(move |s, text, _| {
+                    let note = match check(text) {
+                        Ok(ref value) if preview && value != text.trim() => format!("= {}", value),
+                        Ok(_) => String::new(),
+                        Err(e) => e
+                    };
+                    s.call_on_id(&edit_note_id, |view: &mut views::TextView| view.set_content(note));
+                })
+                .on_submit(move |s, _| submit(s))
+                .with_id(field_id);
+            let row = views::LinearLayout::vertical()
+                .child(views::BoxView::with_fixed_width(20, edit))
+                .child(views::TextView::new("").with_id(note_id));
+            list.add_child(&field.label, row);
+        }
+
+        let dialog = views::Dialog::around(list)
+            .title(self.title)
+            .button("Ok", move |s| submit(s))
+            .dismiss_button("Cancel");
+        s.screen_mut().add_layer_at(Position::new(Offset::Center, Offset::Parent(10)), dialog);
+    }
+}
+
+/// The text of each field of a submitted form.
+pub struct FormValues(Vec<String>);
+
+impl FormValues {
+
+    /// Parses the field at `index`, counting in the order the fields were
+    /// added. Panics if the field doesn't parse as a `T`, which can't happen
+    /// if it was added with the same type.
+    pub fn get<T: FromStr>(&self, index: usize) -> T {
+        T::from_str(&self.0[index]).ok().expect("form field has the wrong type")
+    }
+}
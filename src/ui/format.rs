@@ -0,0 +1,99 @@
+use num_rational::Ratio;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// How the cells of one column are laid out. Numbers are split at their
+/// fraction bar or decimal point, and the two halves are aligned separately
+/// so that `-1/2`, `3` and `12/35` line up on the `/`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub struct ColumnFormat {
+    /// Display width of the widest part before the split point.
+    pub left: usize,
+    /// Display width of the widest part starting at the split point.
+    pub right: usize
+}
+
+impl ColumnFormat {
+
+    /// Computes the format that fits every given cell.
+    pub fn new<'a, I>(cells: I) -> ColumnFormat where I: IntoIterator<Item = &'a str> {
+        cells.into_iter().fold(ColumnFormat::default(), |fmt, cell| {
+            let (left, right) = split_number(cell);
+            ColumnFormat {
+                left: fmt.left.max(left.width()),
+                right: fmt.right.max(right.width())
+            }
+        })
+    }
+
+    /// The display width of every cell in the column.
+    pub fn width(&self) -> usize {
+        self.left + self.right
+    }
+
+    /// Pads the cell so that it lines up with the rest of the column.
+    pub fn align(&self, cell: &str) -> String {
+        let (left, right) = split_number(cell);
+        let mut s = String::with_capacity(self.width());
+        push_spaces(&mut s, self.left.saturating_sub(left.width()));
+        s.push_str(left);
+        s.push_str(right);
+        push_spaces(&mut s, self.right.saturating_sub(right.width()));
+        s
+    }
+}
+
+/// Splits a formatted number at its fraction bar or decimal point.
+fn split_number(cell: &str) -> (&str, &str) {
+    match cell.find(['/', '.']) {
+        Some(i) => cell.split_at(i),
+        None => (cell, "")
+    }
+}
+
+fn push_spaces(s: &mut String, n: usize) {
+    for _ in 0..n {
+        s.push(' ');
+    }
+}
+
+/// Writes the fraction as a decimal rounded to `precision` digits after the
+/// point. Values that round to zero are written without a minus sign.
+pub fn decimal(value: &Ratio<i64>, precision: usize) -> String {
+    let text = format!("{:.*}", precision, *value.numer() as f64 / *value.denom() as f64);
+    if text.starts_with('-') && text[1..].chars().all(|c| c == '0' || c == '.') {
+        text[1..].to_owned()
+    } else {
+        text
+    }
+}
+
+/// Right aligns the text in a field of the given display width.
+pub fn pad_left(text: &str, width: usize) -> String {
+    let mut s = String::with_capacity(width);
+    push_spaces(&mut s, width.saturating_sub(text.width()));
+    s.push_str(text);
+    s
+}
+
+/// Shortens the text to at most `width` columns, replacing the end with an
+/// ellipsis if anything was cut off.
+pub fn truncate(text: &str, width: usize) -> String {
+    if text.width() <= width {
+        return text.to_owned();
+    }
+    if width == 0 {
+        return String::new();
+    }
+    let mut s = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w + 1 > width {
+            break;
+        }
+        s.push(c);
+        used += w;
+    }
+    s.push('\u{2026}');
+    s
+}
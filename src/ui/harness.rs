@@ -0,0 +1,195 @@
+use cursive::Cursive;
+use cursive::event::{Event, EventResult, Key};
+use cursive::traits::View;
+use cursive::vec::Vec2;
+use cursive::views;
+use num_rational::Ratio;
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+use std::sync::{Mutex, MutexGuard};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use config::Config;
+use matrix::Matrix;
+use ui::{app, MatrixView, StatusBar};
+
+/// Only one harness can run at a time, since the terminal and the
+/// environment variables are shared.
+static LOCK: Mutex<()> = Mutex::new(());
+
+static NEXT_DIR: AtomicUsize = AtomicUsize::new(0);
+
+/// The size of the pretend screen that views are laid out in.
+const SCREEN_SIZE: (usize, usize) = (80, 24);
+
+/// Runs the app without anyone at the keyboard, for tests. Events are sent
+/// straight to the views on the screen, so nothing is drawn and `run` is
+/// never called. Global callbacks, such as Escape for the menubar, aren't
+/// triggered.
+///
+/// Files are saved to a temporary directory instead of the user's, and the
+/// harness holds a lock so that tests using it run one at a time. The
+/// environment variables it changes are put back when it is dropped.
+///
+/// Cursive 0.7 has no dummy backend, so `Cursive::new` still starts ncurses.
+/// That works without a terminal attached, such as on a CI runner, but needs
+/// a terminfo entry, so `TERM` is set to `xterm` while the harness runs if it
+/// isn't set.
+///
+/// The harness is only built with the `harness` feature, which is on by
+/// default.
+///
+/// Example:
+/// ```no_run
+/// # extern crate matrixops;
+/// # extern crate num_rational;
+/// # fn main() {
+/// use matrixops::matrix::Matrix;
+/// use matrixops::ui::harness::Harness;
+/// use num_rational::Ratio;
+///
+/// let mut h = Harness::new();
+/// h.open(Matrix::identity(2));
+/// h.press('s');
+/// h.submit_form(&["2", "1/2"]);
+/// assert_eq!(h.matrix().get(2, 2), Some(Ratio::new(1, 2)));
+/// # }
+/// ```
+pub struct Harness {
+    siv: Cursive,
+    dir: PathBuf,
+    /// The environment variables that were changed, with their old values.
+    saved_env: Vec<(&'static str, Option<OsString>)>,
+    _lock: MutexGuard<'static, ()>
+}
+
+impl Default for Harness {
+    fn default() -> Harness {
+        Harness::new()
+    }
+}
+
+impl Harness {
+
+    /// Starts the app with the default config, showing the setup screen.
+    pub fn new() -> Harness {
+        Harness::with_config(Config::default())
+    }
+
+    pub fn with_config(config: Config) -> Harness {
+        // A test that panicked while holding the lock doesn't matter to the
+        // next one.
+        let lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+        let dir = env::temp_dir().join(format!("matrops-harness-{}-{}", process::id(),
+                                               NEXT_DIR.fetch_add(1, Ordering::Relaxed)));
+        let mut vars = vec![("XDG_DATA_HOME", dir.join("data").into_os_string()),
+                            ("XDG_CONFIG_HOME", dir.join("config").into_os_string())];
+        if env::var_os("TERM").is_none() {
+            vars.push(("TERM", OsString::from("xterm")));
+        }
+        let saved_env = vars.into_iter()
+            .map(|(name, value)| {
+                let old = env::var_os(name);
+                env::set_var(name, value);
+                (name, old)
+            })
+            .collect();
+
+        let mut siv = Cursive::new();
+        app::start(&mut siv, config);
+        Harness { siv, dir, saved_env, _lock: lock }
+    }
+
+    /// Replaces the setup screen with the matrix, as if it had been pasted.
+    pub fn open(&mut self, matrix: Matrix<Ratio<i64>>) {
+        self.siv.pop_layer();
+        app::show_edit_view(&mut self.siv, matrix);
+    }
+
+    /// Sends the event to the view in focus, and runs the callback it
+    /// returns, if any.
+    pub fn send(&mut self, event: Event) {
+        // Views find out their size and what can be focused in `layout`,
+        // which `run` does before every event.
+        self.siv.screen_mut().layout(Vec2::new(SCREEN_SIZE.0, SCREEN_SIZE.1));
+        if let EventResult::Consumed(Some(cb)) = self.siv.screen_mut().on_event(event) {
+            cb(&mut self.siv);
+        }
+    }
+
+    pub fn press(&mut self, c: char) {
+        self.send(Event::Char(c));
+    }
+
+    pub fn press_key(&mut self, key: Key) {
+        self.send(Event::Key(key));
+    }
+
+    /// Types each character of the text.
+    pub fn type_text(&mut self, text: &str) {
+        for c in text.chars() {
+            self.press(c);
+        }
+    }
+
+    /// Fills in the fields of the open form from the top, and submits it
+    /// with Enter.
+    pub fn submit_form(&mut self, values: &[&str]) {
+        for (i, value) in values.iter().enumerate() {
+            if i > 0 {
+                self.press_key(Key::Down);
+            }
+            self.type_text(value);
+        }
+        self.press_key(Key::Enter);
+    }
+
+    /// The matrix being edited. Panics if there isn't one.
+    pub fn matrix(&mut self) -> Matrix<Ratio<i64>> {
+        self.siv.find_id::<MatrixView<Ratio<i64>>>("matrix_view")
+            .expect("matrix view not found")
+            .matrix
+            .clone()
+    }
+
+    pub fn status(&mut self) -> String {
+        self.siv.find_id::<StatusBar>("status_bar").expect("status bar not found").text()
+    }
+
+    /// The text of the view with the id, if it is on the screen.
+    pub fn text(&mut self, id: &str) -> Option<String> {
+        self.siv.find_id::<views::TextView>(id).map(|view| view.get_content().to_owned())
+    }
+
+    /// How many layers are on the screen, such as forms and dialogs over
+    /// the matrix.
+    pub fn layers(&mut self) -> usize {
+        self.siv.screen_mut().layer_sizes().len()
+    }
+
+    /// The message of the open error popup, if there is one.
+    pub fn error(&mut self) -> Option<String> {
+        self.text("error_text")
+    }
+
+    /// The Cursive instance, for anything the other methods don't cover.
+    pub fn cursive(&mut self) -> &mut Cursive {
+        &mut self.siv
+    }
+}
+
+impl Drop for Harness {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+        for (name, old) in self.saved_env.drain(..) {
+            match old {
+                Some(value) => env::set_var(name, value),
+                None => env::remove_var(name)
+            }
+        }
+    }
+}
@@ -1,101 +1,432 @@
-use cursive;
-use cursive::view::{Finder, ViewWrapper};
-use cursive::views;
-use cursive::align::HAlign;
-use cursive::traits::{Identifiable, View};
+use cursive::Printer;
+use cursive::direction::Direction;
+use cursive::event::{Event, EventResult, Key};
+use cursive::theme::ColorStyle;
+use cursive::traits::View;
+use cursive::vec::Vec2;
+use num_traits::{One, Zero};
 use std;
-use std::ops::{Add, Mul};
+use std::fmt;
+use std::ops::Range;
+use std::time::{Duration, Instant};
 
-use matrix::{Matrix, MatrixResult};
-use ui::command::Command;
+use config::{ColorRole, Colors};
+use matrix::{Field, Matrix, MatrixResult};
+use reduce::EchelonForm;
+use ui::command::{ApplyMode, Command};
+use ui::format::{pad_left, truncate, ColumnFormat};
 
+/// Cells wider than this are truncated with an ellipsis, when the whole matrix
+/// is too wide for the screen.
+const MAX_CELL_WIDTH: usize = 16;
+
+/// How long the cells changed by a command stay highlighted.
+const HIGHLIGHT_MILLIS: u64 = 1500;
+
+/// Width set aside for the command shown next to the rows it affected, so the
+/// view doesn't change size while a change is highlighted. Longer commands are
+/// truncated.
+const NOTE_WIDTH: usize = 24;
+
+/// How the columns of a `MatrixView` are labelled.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ColumnLabels {
+    /// `C1` to `Cn`.
+    Numbered,
+    /// `x1` to `x(n-1)` followed by `b`, for the augmented matrix of a linear
+    /// system.
+    Variables
+}
+
+/// Formats an entry of a `MatrixView`.
+type Formatter<T> = Box<dyn Fn(&T) -> String>;
+
+/// A view that draws the visible part of a matrix, with row and column labels
+/// that stay in place while scrolling.
 pub struct MatrixView<T> {
     pub matrix: Matrix<T>,
-    underlying: views::LinearLayout
+    /// The matrix before the last applied command.
+    previous: Option<Matrix<T>>,
+    history: Vec<Command<T>>,
+    echelon: EchelonForm,
+    column_labels: ColumnLabels,
+    highlight_changes: bool,
+    change: Option<Change>,
+    colors: Colors,
+    /// Formats each entry, if `to_string` shouldn't be used.
+    formatter: Option<Formatter<T>>,
+    /// The (0-based) row and column shown in the top left corner.
+    offset: Vec2,
+    /// How many rows and columns fit in the last layout.
+    visible: Vec2,
+    /// The matrix that `cells` and `formats` were computed from, so they are
+    /// only recomputed when the matrix changes.
+    cached: Option<Matrix<T>>,
+    cells: Vec<Vec<Cell>>,
+    formats: Vec<ColumnFormat>,
+    /// How each column is aligned once its cells are truncated.
+    short_formats: Vec<ColumnFormat>,
+    /// Whether cells are truncated, because the matrix didn't fit in the last
+    /// layout.
+    truncated: bool
 }
 
-impl <T: Clone + std::string::ToString> MatrixView<T> {
+/// What the last applied command changed.
+struct Change {
+    /// The (1-based) cells whose values changed.
+    cells: Vec<(usize, usize)>,
+    /// The (1-based) rows the command operated on.
+    rows: Vec<usize>,
+    text: String,
+    time: Instant
+}
 
-    pub fn new(matrix: Matrix<T>) -> Self {
-        let mut row_views = views::LinearLayout::vertical();
-        let max_width = Self::max_cell_size(&matrix);
-
-        for (row_ind, row) in matrix.rows().iter().enumerate() {
-            let mut rview = views::LinearLayout::horizontal();
-            for (col_ind, value) in row.iter().enumerate() {
-                let cell_id = Self::cell_id(row_ind, col_ind);
-                let cell = views::TextView::new(Self::cell_text(value, max_width))
-                    .h_align(HAlign::Right)
-                    .with_id(cell_id.as_str());
-                rview.add_child(views::DummyView {});
-                rview.add_child(cell);
-            }
-            row_views.add_child(rview);
-        }
+/// The formatted text of one cell.
+struct Cell {
+    text: String,
+    /// `text`, truncated to `MAX_CELL_WIDTH`.
+    short: String,
+    style: ColorStyle
+}
 
+impl <T: Clone + std::string::ToString + PartialEq + Zero + One> MatrixView<T> {
+
+    pub fn new(matrix: Matrix<T>) -> Self {
         MatrixView {
+            echelon: matrix.echelon_form(),
             matrix: matrix,
-            underlying: row_views
+            previous: None,
+            history: Vec::new(),
+            column_labels: ColumnLabels::Numbered,
+            highlight_changes: true,
+            change: None,
+            colors: Colors::default(),
+            formatter: None,
+            offset: Vec2::zero(),
+            visible: Vec2::zero(),
+            cached: None,
+            cells: Vec::new(),
+            formats: Vec::new(),
+            short_formats: Vec::new(),
+            truncated: false
         }
     }
 
     pub fn apply_command(&mut self, cmd: Command<T>) -> MatrixResult<()>
-        where T: Add<T, Output = T> + Mul<T, Output = T> {
-        let upd = cmd.apply(&self.matrix)?;
-        self.matrix = upd;
+        where T: Field + fmt::Display {
+        let mut rows: Vec<usize> = cmd.expand(&self.matrix)?.iter().flat_map(|step| step.affected_rows()).collect();
+        rows.sort();
+        rows.dedup();
+        let previous = self.matrix.clone();
+        cmd.apply_in_place(&mut self.matrix, ApplyMode::Strict)?;
+        self.echelon = self.matrix.echelon_form();
+        if self.highlight_changes {
+            self.change = Some(Change {
+                cells: previous.diff(&self.matrix),
+                rows,
+                text: cmd.to_string(),
+                time: Instant::now()
+            });
+        }
+        self.previous = Some(previous);
+        self.history.push(cmd);
         Ok(())
     }
 
-    fn max_cell_size(matrix: &Matrix<T>) -> usize {
-        matrix.rows().iter()
-            .flat_map(|r| r)
-            .map(|v| v.to_string().len() as usize)
-            .max()
-            .unwrap()
-    }
-
-    fn cell_text(value: &T, max_size: usize) -> String {
-        let mut s = value.to_string();
-        let padding = std::iter::repeat(" ")
-            .take(max_size - s.len())
-            .collect::<String>();
-        s.push_str(&padding);
-        s
-    }
-
-    fn update(&mut self) {
-        let mat = &self.matrix;
-        let max_width = Self::max_cell_size(mat);
-        for (row_ind, row) in mat.rows().iter().enumerate() {
-            for (col_ind, value) in row.iter().enumerate() {
-                let id = Self::cell_id(row_ind, col_ind);
-                self.underlying.find_id(&id, |view: &mut views::TextView| {
-                    view.set_content(Self::cell_text(value, max_width));
-                });
+    /// The commands applied so far, oldest first.
+    pub fn history(&self) -> &[Command<T>] {
+        &self.history
+    }
+
+    /// Replaces the history, such as when restoring a saved session. The
+    /// matrix isn't changed.
+    pub fn set_history(&mut self, history: Vec<Command<T>>) {
+        self.history = history;
+    }
+
+    /// The matrix before the last applied command, if any.
+    pub fn previous_matrix(&self) -> Option<&Matrix<T>> {
+        self.previous.as_ref()
+    }
+
+    pub fn highlight_changes(&self) -> bool {
+        self.highlight_changes
+    }
+
+    /// Sets whether the cells changed by a command are briefly highlighted,
+    /// with the command shown next to the rows it affected.
+    pub fn set_highlight_changes(&mut self, enabled: bool) {
+        self.highlight_changes = enabled;
+        if !enabled {
+            self.change = None;
+        }
+    }
+
+    /// The last change, if it is recent enough to still be highlighted.
+    fn recent_change(&self) -> Option<&Change> {
+        let duration = Duration::from_millis(HIGHLIGHT_MILLIS);
+        self.change.as_ref().filter(|change| change.time.elapsed() < duration)
+    }
+
+    /// Whether a change is still highlighted, so the view needs redrawing
+    /// once it fades out.
+    pub fn is_highlighting(&self) -> bool {
+        self.recent_change().is_some()
+    }
+
+    /// Whether the matrix is in REF, RREF or neither, as of the last applied
+    /// command.
+    pub fn echelon_form(&self) -> EchelonForm {
+        self.echelon
+    }
+
+    pub fn column_labels(&self) -> ColumnLabels {
+        self.column_labels
+    }
+
+    pub fn set_column_labels(&mut self, labels: ColumnLabels) {
+        self.column_labels = labels;
+    }
+
+    pub fn set_colors(&mut self, colors: Colors) {
+        self.colors = colors;
+        self.cached = None;
+    }
+
+    /// Sets how entries are written, such as to show fractions as decimals.
+    pub fn set_formatter<F: 'static + Fn(&T) -> String>(&mut self, formatter: F) {
+        self.formatter = Some(Box::new(formatter));
+        self.cached = None;
+    }
+
+    fn row_label(row: usize) -> String {
+        format!("R{}", row + 1)
+    }
+
+    fn column_label(&self, col: usize) -> String {
+        match self.column_labels {
+            ColumnLabels::Numbered => format!("C{}", col + 1),
+            ColumnLabels::Variables if col + 1 == self.matrix.cols => "b".to_owned(),
+            ColumnLabels::Variables => format!("x{}", col + 1)
+        }
+    }
+
+    /// Pivots are highlighted and zeros are dimmed, so it is easy to see how
+    /// far along a reduction is.
+    fn cell_style(&self, value: &T, is_pivot: bool) -> ColorStyle {
+        if is_pivot {
+            color_style(self.colors.pivots)
+        } else if value.is_zero() {
+            color_style(self.colors.zeros)
+        } else {
+            color_style(self.colors.entries)
+        }
+    }
+
+    fn format(&self, value: &T) -> String {
+        match self.formatter {
+            Some(ref formatter) => formatter(value),
+            None => value.to_string()
+        }
+    }
+
+    /// Formats every cell, in full and truncated, and computes how each column
+    /// should be aligned either way. Does nothing if the matrix hasn't changed.
+    fn refresh(&mut self) {
+        if self.cached.as_ref() == Some(&self.matrix) {
+            return;
+        }
+        let leading = self.matrix.leading_entries();
+        let cells = self.matrix.rows()
+            .iter()
+            .enumerate()
+            .map(|(r, row)| {
+                row.iter()
+                    .enumerate()
+                    .map(|(c, v)| {
+                        let text = self.format(v);
+                        Cell {
+                            short: truncate(&text, MAX_CELL_WIDTH),
+                            text,
+                            style: self.cell_style(v, leading[r] == Some(c + 1))
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        self.cells = cells;
+        let cells = &self.cells;
+        self.formats = (0..self.matrix.cols)
+            .map(|c| ColumnFormat::new(cells.iter().map(|row| row[c].text.as_str())))
+            .collect();
+        self.short_formats = (0..self.matrix.cols)
+            .map(|c| ColumnFormat::new(cells.iter().map(|row| row[c].short.as_str())))
+            .collect();
+        self.cached = Some(self.matrix.clone());
+    }
+
+    /// Width of the row labels on the left.
+    fn header_width(&self) -> usize {
+        Self::row_label(self.matrix.rows.saturating_sub(1)).len()
+    }
+
+    /// How the columns are aligned, depending on whether cells are truncated.
+    fn formats(&self) -> &[ColumnFormat] {
+        if self.truncated { &self.short_formats } else { &self.formats }
+    }
+
+    /// The text of a (0-based) cell, truncated if the matrix doesn't fit.
+    fn cell_text(&self, row: usize, col: usize) -> &str {
+        let cell = &self.cells[row][col];
+        if self.truncated { &cell.short } else { &cell.text }
+    }
+
+    /// Width of a column, which is wide enough for its cells and its label.
+    fn column_width(&self, col: usize) -> usize {
+        self.formats()[col].width().max(self.column_label(col).len())
+    }
+
+    /// Width of the whole matrix with its row labels, with cells in full.
+    fn full_width(&self) -> usize {
+        (0..self.matrix.cols).fold(self.header_width(), |acc, c| {
+            acc + self.formats[c].width().max(self.column_label(c).len()) + 1
+        })
+    }
+
+    /// The (0-based) columns that fit in the given width. Each column is
+    /// preceded by a space, and at least one column is always shown, even if
+    /// it has to be cut off.
+    fn visible_cols(&self, width: usize) -> Vec<usize> {
+        let mut used = self.header_width();
+        let mut cols = Vec::new();
+        for c in self.offset.x..self.matrix.cols {
+            let w = self.column_width(c) + 1;
+            if used + w > width && !cols.is_empty() {
+                break;
             }
+            used += w;
+            cols.push(c);
         }
+        cols
+    }
+
+    /// The (0-based) rows that fit in the given height, below the column
+    /// labels.
+    fn visible_rows(&self, height: usize) -> Range<usize> {
+        let end = self.matrix.rows.min(self.offset.y + height.saturating_sub(1));
+        self.offset.y..end.max(self.offset.y)
+    }
+
+    fn can_scroll(&self) -> bool {
+        self.visible.x < self.matrix.cols || self.visible.y < self.matrix.rows
     }
 
-    fn cell_id(row_ind: usize, col_ind: usize) -> String {
-        format!("cell_{},{}", row_ind + 1, col_ind + 1)
+    fn scroll(&mut self, key: Key) -> bool {
+        let last_row = self.offset.y + self.visible.y;
+        let last_col = self.offset.x + self.visible.x;
+        match key {
+            Key::Up if self.offset.y > 0 => self.offset.y -= 1,
+            Key::Down if last_row < self.matrix.rows => self.offset.y += 1,
+            Key::Left if self.offset.x > 0 => self.offset.x -= 1,
+            Key::Right if last_col < self.matrix.cols => self.offset.x += 1,
+            Key::PageUp if self.offset.y > 0 => {
+                self.offset.y = self.offset.y.saturating_sub(self.visible.y);
+            },
+            Key::PageDown if last_row < self.matrix.rows => {
+                self.offset.y = last_row.min(self.matrix.rows - self.visible.y);
+            },
+            _ => return false
+        }
+        true
     }
 }
 
-impl <T: Clone + ToString> ViewWrapper for MatrixView<T> {
-    type V = views::LinearLayout;
+impl <T: 'static + Clone + ToString + PartialEq + Zero + One> View for MatrixView<T> {
+
+    fn draw(&self, printer: &Printer) {
+        let header_width = self.header_width();
+        let cols = self.visible_cols(printer.size.x);
+        let rows = self.visible_rows(printer.size.y);
 
-    fn wrap_layout(&mut self, size: cursive::vec::Vec2) -> () {
-        self.update();
-        self.with_view_mut(|v| v.layout(size));
+        printer.with_color(color_style(self.colors.labels), |printer| {
+            let mut x = header_width + 1;
+            for &c in &cols {
+                let w = self.column_width(c);
+                let label = truncate(&pad_left(&self.column_label(c), w), printer.size.x.saturating_sub(x));
+                printer.print((x, 0), &label);
+                x += w + 1;
+            }
+            for (y, r) in rows.clone().enumerate() {
+                printer.print((0, y + 1), &pad_left(&Self::row_label(r), header_width));
+            }
+        });
+
+        let change = self.recent_change();
+        for (y, r) in rows.enumerate() {
+            let mut x = header_width + 1;
+            for &c in &cols {
+                let w = self.column_width(c);
+                let text = pad_left(&self.formats()[c].align(self.cell_text(r, c)), w);
+                let text = truncate(&text, printer.size.x.saturating_sub(x));
+                let style = match change {
+                    Some(change) if change.cells.contains(&(r + 1, c + 1)) => color_style(self.colors.changes),
+                    _ => self.cells[r][c].style
+                };
+                printer.with_color(style, |printer| printer.print((x, y + 1), &text));
+                x += w + 1;
+            }
+
+            // Mark the rows that the command operated on.
+            if let Some(change) = change {
+                if change.rows.contains(&(r + 1)) {
+                    let text = truncate(&format!(" < {}", change.text), printer.size.x.saturating_sub(x));
+                    printer.with_color(color_style(self.colors.notes), |printer| printer.print((x, y + 1), &text));
+                }
+            }
+        }
+    }
+
+    fn required_size(&mut self, constraint: Vec2) -> Vec2 {
+        self.refresh();
+        let mut width = self.full_width();
+        if self.highlight_changes {
+            width += NOTE_WIDTH;
+        }
+        Vec2::min(Vec2::new(width, self.matrix.rows + 1), constraint)
+    }
+
+    fn layout(&mut self, size: Vec2) {
+        self.refresh();
+        // Cells are only cut short when scrolling alone couldn't show them.
+        self.truncated = self.full_width() > size.x;
+        self.offset.y = self.offset.y.min(self.matrix.rows.saturating_sub(1));
+        self.offset.x = self.offset.x.min(self.matrix.cols.saturating_sub(1));
+        let rows = self.visible_rows(size.y);
+        self.visible = Vec2::new(self.visible_cols(size.x).len(), rows.end - rows.start);
     }
 
-    fn with_view<F, R>(&self, f: F) -> Option<R>
-        where F: FnOnce(&Self::V) -> R {
-        Some(f(&self.underlying))
+    fn on_event(&mut self, event: Event) -> EventResult {
+        // Keys are only consumed while there is somewhere to scroll to, so
+        // focus can still move out of the view at the edges.
+        match event {
+            Event::Key(key) if self.scroll(key) => EventResult::Consumed(None),
+            _ => EventResult::Ignored
+        }
     }
 
-    fn with_view_mut<F, R>(&mut self, f: F) -> Option<R>
-        where F: FnOnce(&mut Self::V) -> R {
-        Some(f(&mut self.underlying))
+    fn take_focus(&mut self, _: Direction) -> bool {
+        self.can_scroll()
+    }
+}
+
+fn color_style(role: ColorRole) -> ColorStyle {
+    match role {
+        ColorRole::Primary => ColorStyle::Primary,
+        ColorRole::Secondary => ColorStyle::Secondary,
+        ColorRole::Tertiary => ColorStyle::Tertiary,
+        ColorRole::TitlePrimary => ColorStyle::TitlePrimary,
+        ColorRole::TitleSecondary => ColorStyle::TitleSecondary,
+        ColorRole::Highlight => ColorStyle::Highlight,
+        ColorRole::HighlightInactive => ColorStyle::HighlightInactive
     }
 }
@@ -0,0 +1,180 @@
+use cursive::Printer;
+use cursive::theme::ColorStyle;
+use cursive::traits::View;
+use cursive::vec::Vec2;
+use num_traits::{One, Zero};
+use std::fmt::Display;
+use std::time::{Duration, Instant};
+use unicode_width::UnicodeWidthStr;
+
+use config::Notation;
+use reduce::EchelonForm;
+use ui::MatrixView;
+use ui::format::truncate;
+
+/// How long messages stay in the status bar.
+const MESSAGE_MILLIS: u64 = 5000;
+
+/// A line at the bottom of the screen that describes the matrix being
+/// edited, followed by the latest message.
+pub struct StatusBar {
+    /// The rows and columns of the matrix, once there is one.
+    size: Option<(usize, usize)>,
+    echelon: Option<EchelonForm>,
+    last_command: Option<String>,
+    notation: String,
+    /// Whether the last autosave failed, so that the matrix has changes that
+    /// aren't saved. Saving normally happens right after every change.
+    unsaved: bool,
+    recording: bool,
+    /// The moves and invalid moves made in the quiz being taken, if any.
+    quiz: Option<(usize, usize)>,
+    message: Option<Message>
+}
+
+struct Message {
+    text: String,
+    error: bool,
+    time: Instant
+}
+
+impl Default for StatusBar {
+    fn default() -> StatusBar {
+        StatusBar::new()
+    }
+}
+
+impl StatusBar {
+
+    pub fn new() -> StatusBar {
+        StatusBar {
+            size: None,
+            echelon: None,
+            last_command: None,
+            notation: "fractions".to_owned(),
+            unsaved: false,
+            recording: false,
+            quiz: None,
+            message: None
+        }
+    }
+
+    /// Describes the matrix shown in the view, and the last command applied
+    /// to it.
+    pub fn update<T>(&mut self, view: &MatrixView<T>) where T: Clone + Display + PartialEq + Zero + One {
+        self.size = Some((view.matrix.rows, view.matrix.cols));
+        self.echelon = Some(view.echelon_form());
+        self.last_command = view.history().last().map(|cmd| cmd.to_string());
+    }
+
+    /// Forgets the matrix, such as when going back to the setup screen.
+    pub fn clear(&mut self) {
+        self.size = None;
+        self.echelon = None;
+        self.last_command = None;
+        self.unsaved = false;
+        self.quiz = None;
+    }
+
+    pub fn set_notation(&mut self, notation: Notation, precision: usize) {
+        self.notation = match notation {
+            Notation::Fraction => "fractions".to_owned(),
+            Notation::Decimal => format!("{} decimals", precision)
+        };
+    }
+
+    pub fn set_unsaved(&mut self, unsaved: bool) {
+        self.unsaved = unsaved;
+    }
+
+    pub fn set_recording(&mut self, recording: bool) {
+        self.recording = recording;
+    }
+
+    /// Shows how many moves and invalid moves have been made in a quiz, or
+    /// nothing if `None`.
+    pub fn set_quiz(&mut self, moves: Option<(usize, usize)>) {
+        self.quiz = moves;
+    }
+
+    /// Shows a message for a few seconds.
+    pub fn info<S: Into<String>>(&mut self, text: S) {
+        self.show(text.into(), false);
+    }
+
+    /// Shows a message for a few seconds, highlighted.
+    pub fn error<S: Into<String>>(&mut self, text: S) {
+        self.show(text.into(), true);
+    }
+
+    fn show(&mut self, text: String, error: bool) {
+        self.message = Some(Message { text, error, time: Instant::now() });
+    }
+
+    fn recent_message(&self) -> Option<&Message> {
+        let duration = Duration::from_millis(MESSAGE_MILLIS);
+        self.message.as_ref().filter(|message| message.time.elapsed() < duration)
+    }
+
+    /// Whether a message is still shown, so the bar needs redrawing once it
+    /// fades out.
+    pub fn has_message(&self) -> bool {
+        self.recent_message().is_some()
+    }
+
+    /// The parts of the status that describe the state of the app.
+    fn state(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some((rows, cols)) = self.size {
+            parts.push(format!("{}x{}", rows, cols));
+        }
+        // Matrices always hold exact rationals; the notation is only how
+        // they are shown.
+        parts.push("rational".to_owned());
+        parts.push(self.notation.clone());
+        if let Some(echelon) = self.echelon {
+            parts.push(echelon.to_string());
+        }
+        if let Some(ref cmd) = self.last_command {
+            parts.push(format!("last: {}", cmd));
+        }
+        match self.quiz {
+            Some((moves, 0)) => parts.push(format!("moves: {}", moves)),
+            Some((moves, invalid)) => parts.push(format!("moves: {} ({} invalid)", moves, invalid)),
+            None => {}
+        }
+        if self.unsaved {
+            parts.push("unsaved".to_owned());
+        }
+        if self.recording {
+            parts.push("recording".to_owned());
+        }
+        parts.join(" | ")
+    }
+
+    /// The whole status line, as it is drawn.
+    pub fn text(&self) -> String {
+        match self.recent_message() {
+            Some(message) => format!("{} | {}", self.state(), message.text),
+            None => self.state()
+        }
+    }
+}
+
+impl View for StatusBar {
+
+    fn draw(&self, printer: &Printer) {
+        let state = truncate(&self.state(), printer.size.x);
+        printer.print((0, 0), &state);
+        if let Some(message) = self.recent_message() {
+            let x = state.width();
+            let text = truncate(&format!(" | {}", message.text), printer.size.x.saturating_sub(x));
+            let style = if message.error { ColorStyle::Highlight } else { ColorStyle::Primary };
+            printer.with_color(style, |printer| printer.print((x, 0), &text));
+        }
+    }
+
+    fn required_size(&mut self, constraint: Vec2) -> Vec2 {
+        Vec2::new(self.text().width().min(constraint.x), 1)
+    }
+}
@@ -1,7 +1,7 @@
 extern crate matrixops;
 
 use matrixops::matrix::Matrix;
-use matrixops::ui::command::Command;
+use matrixops::ui::command::{ApplyMode, Command, CommandSequence};
 
 #[test]
 fn command_add() {
@@ -57,3 +57,171 @@ fn command_scale() {
         0.0, 0.0, 1.0
     ]));
 }
+
+#[test]
+fn command_display() {
+    let cmd = Command::AddRow {
+        coeff: -2,
+        src: 1,
+        dest: 3
+    };
+    assert_eq!(cmd.to_string(), "R3 += (-2)R1");
+    assert_eq!(cmd.affected_rows(), vec![3]);
+
+    let cmd: Command<i32> = Command::SwapRow {
+        row1: 1,
+        row2: 2
+    };
+    assert_eq!(cmd.to_string(), "R1 <-> R2");
+    assert_eq!(cmd.affected_rows(), vec![1, 2]);
+}
+
+#[test]
+fn command_strict() {
+    let m1 = Matrix::new(2, 2, vec![1, 2,
+                                    3, 4]);
+    let scale = Command::ScaleRow {
+        coeff: 0,
+        row: 1
+    };
+    let add = Command::AddRow {
+        coeff: 1,
+        src: 2,
+        dest: 2
+    };
+    assert!(scale.apply_with(&m1, ApplyMode::Strict).is_err());
+    assert!(add.apply_with(&m1, ApplyMode::Strict).is_err());
+
+    assert_eq!(scale.apply_with(&m1, ApplyMode::Permissive).unwrap(), Matrix::new(2, 2, vec![
+        0, 0,
+        3, 4
+    ]));
+    assert_eq!(add.apply_with(&m1, ApplyMode::Permissive).unwrap(), Matrix::new(2, 2, vec![
+        1, 2,
+        6, 8
+    ]));
+
+    let edit = Command::EditCell {
+        row: 1,
+        col: 1,
+        value: 0
+    };
+    assert!(edit.apply(&m1).is_ok());
+    assert_eq!(scale.apply(&m1), scale.apply_with(&m1, ApplyMode::Permissive));
+    assert_eq!(add.apply(&m1), add.apply_with(&m1, ApplyMode::Permissive));
+}
+
+#[test]
+fn command_parse() {
+    let cmds: Vec<Command<i32>> = vec![
+        Command::AddRow { coeff: -2, src: 1, dest: 3 },
+        Command::ScaleRow { coeff: 4, row: 2 },
+        Command::SwapRow { row1: 1, row2: 2 },
+        Command::EditCell { row: 2, col: 3, value: -7 },
+        Command::EditRow { row: 1, values: vec![1, 2, 3] }
+    ];
+    for cmd in cmds {
+        assert_eq!(cmd.to_string().parse::<Command<i32>>(), Ok(cmd));
+    }
+    assert!("R1 += 2R2".parse::<Command<i32>>().is_err());
+    assert!("R1 = 3".parse::<Command<i32>>().is_err());
+    assert!("X1 <-> R2".parse::<Command<i32>>().is_err());
+}
+
+#[test]
+fn command_sequence() {
+    let mut seq = CommandSequence::new("eliminate");
+    seq.push(Command::AddRow { coeff: -3, src: 1, dest: 2 });
+    seq.push(Command::SwapRow { row1: 1, row2: 2 });
+
+    let m1 = Matrix::new(2, 2, vec![1, 2,
+                                    3, 4]);
+    assert_eq!(seq.apply(&m1).unwrap(), Matrix::new(2, 2, vec![
+        0, -2,
+        1, 2
+    ]));
+
+    let remapped = seq.remap_rows(&[2, 1]).unwrap();
+    assert_eq!(remapped.commands[0], Command::AddRow { coeff: -3, src: 2, dest: 1 });
+    assert!(seq.remap_rows(&[1]).is_err());
+
+    let bad = CommandSequence { name: "bad".to_owned(), commands: vec![Command::ScaleRow { coeff: 0, row: 1 }] };
+    assert!(bad.apply(&m1).is_err());
+
+    let text = format!("# saved sequences\n{}\n{}", seq.to_text(), bad.to_text());
+    assert_eq!(CommandSequence::parse_all(&text), Ok(vec![seq, bad]));
+    assert!(CommandSequence::<i32>::parse_all("R1 <-> R2").is_err());
+}
+
+#[test]
+fn command_composite() {
+    let m1 = Matrix::new(3, 2, vec![2.0, 4.0,
+                                    1.0, 1.0,
+                                    -4.0, 0.0]);
+    let eliminate = Command::EliminateColumn {
+        pivot_row: 1,
+        col: 1
+    };
+    assert_eq!(eliminate.expand(&m1).unwrap(), vec![
+        Command::AddRow { coeff: -0.5, src: 1, dest: 2 },
+        Command::AddRow { coeff: 2.0, src: 1, dest: 3 }
+    ]);
+    assert_eq!(eliminate.apply(&m1).unwrap(), Matrix::new(3, 2, vec![
+        2.0, 4.0,
+        0.0, -1.0,
+        0.0, 8.0
+    ]));
+    assert_eq!(eliminate.affected_rows(), vec![]);
+    assert!(Command::EliminateColumn { pivot_row: 2, col: 2 }.apply(&eliminate.apply(&m1).unwrap()).is_ok());
+    assert!(Command::EliminateColumn { pivot_row: 1, col: 3 }.apply(&m1).is_err());
+
+    let normalize = Command::NormalizePivot { row: 1 };
+    assert_eq!(normalize.expand(&m1).unwrap(), vec![Command::ScaleRow { coeff: 0.5, row: 1 }]);
+    assert!(Command::NormalizePivot { row: 1 }.apply(&Matrix::new(1, 2, vec![0.0, 0.0])).is_err());
+
+    let permute: Command<f64> = Command::PermuteRows(vec![3, 1, 2]);
+    assert_eq!(permute.apply(&m1).unwrap(), Matrix::new(3, 2, vec![
+        -4.0, 0.0,
+        2.0, 4.0,
+        1.0, 1.0
+    ]));
+    assert_eq!(permute.expand(&m1).unwrap().len(), 2);
+    assert_eq!(permute.affected_rows(), vec![1, 2, 3]);
+    assert!(Command::PermuteRows(vec![1, 1, 2]).apply(&m1).is_err());
+    assert!(Command::PermuteRows(vec![1, 2]).apply(&m1).is_err());
+
+    // Remapping a reordering moves it along with the rows.
+    let remapped = permute.remap_rows(&[2, 1, 3]).unwrap();
+    assert_eq!(remapped, Command::PermuteRows(vec![2, 3, 1]));
+
+    for cmd in [eliminate, normalize, permute] {
+        assert!(cmd.is_composite());
+        assert_eq!(cmd.to_string().parse::<Command<f64>>(), Ok(cmd));
+    }
+}
+
+#[test]
+fn command_bounds() {
+    let m = Matrix::new(2, 2, vec![1, 2,
+                                   3, 4]);
+    for &row in &[0, 3] {
+        let cmds = vec![
+            Command::AddRow { coeff: 1, src: row, dest: 1 },
+            Command::AddRow { coeff: 1, src: 1, dest: row },
+            Command::ScaleRow { coeff: 2, row },
+            Command::SwapRow { row1: 1, row2: row },
+            Command::EditCell { row, col: 1, value: 0 },
+            Command::EditCell { row: 1, col: row, value: 0 },
+            Command::EditRow { row, values: vec![0, 0] },
+            Command::EliminateColumn { pivot_row: row, col: 1 },
+            Command::EliminateColumn { pivot_row: 1, col: row },
+            Command::NormalizePivot { row },
+            Command::PermuteRows(vec![row, 1])
+        ];
+        for cmd in cmds {
+            let mut upd = m.clone();
+            assert!(cmd.apply_in_place(&mut upd, ApplyMode::Permissive).is_err(), "{}", cmd);
+            assert_eq!(upd, m, "{}", cmd);
+        }
+    }
+}
@@ -0,0 +1,102 @@
+extern crate matrixops;
+extern crate num_rational;
+
+use matrixops::config::{ColorRole, Colors, Config, Notation};
+use matrixops::export::{export, ExportFormat};
+use matrixops::matrix::Matrix;
+use matrixops::parse::parse_matrix;
+use matrixops::ui::Action;
+use num_rational::Ratio;
+use std::path::Path;
+
+#[test]
+fn config_defaults() {
+    let config = Config::parse("").unwrap();
+    assert_eq!(config, Config::default());
+    assert_eq!(config.key(Action::Scale), 's');
+    assert_eq!(config.key(Action::EditRow), 'E');
+    assert_eq!(config.notation, Notation::Fraction);
+    assert_eq!(config.export_format, ExportFormat::Plain);
+    assert!(!config.export_subspaces);
+    assert_eq!(Config::load(Path::new("/nonexistent/matrops/config.toml")), Ok(Config::default()));
+
+    // Every action has its own key by default.
+    let bindings = config.bindings();
+    assert_eq!(bindings.len(), Action::all().len());
+    for (i, &(_, key)) in bindings.iter().enumerate() {
+        assert!(bindings[(i + 1)..].iter().all(|&(_, k)| k != key), "{} is bound twice", key);
+    }
+}
+
+#[test]
+fn config_parse() {
+    let text = "# Settings\n\
+                [keys]\n\
+                scale = \"m\"\n\
+                swap = 's'\n\
+                \n\
+                [colors]\n\
+                zeros = \"tertiary\"\n\
+                theme = \"contrast\"\n\
+                \n\
+                [numbers]\n\
+                notation = \"decimal\"\n\
+                precision = 5\n\
+                \n\
+                [export]\n\
+                format = latex\n\
+                subspaces = true\n";
+    let config = Config::parse(text).unwrap();
+    assert_eq!(config.key(Action::Scale), 'm');
+    assert_eq!(config.key(Action::Swap), 's');
+    assert_eq!(config.key(Action::Add), 'a');
+    assert_eq!(config.colors, Colors { zeros: ColorRole::Tertiary, ..Colors::theme("contrast").unwrap() });
+    assert_eq!(config.notation, Notation::Decimal);
+    assert_eq!(config.precision, 5);
+    assert_eq!(config.export_format, ExportFormat::Latex);
+    assert!(config.export_subspaces);
+}
+
+#[test]
+fn config_errors() {
+    assert_eq!(Config::parse("[keys]\nscale = m\nswap = m"), Err("m is bound to both scale and swap".to_owned()));
+    assert_eq!(Config::parse("[keys]\nscale = a"), Err("a is bound to both scale and add".to_owned()));
+    assert_eq!(Config::parse("[keys]\nscale = ab"), Err("line 2: the key for scale must be one character".to_owned()));
+    assert_eq!(Config::parse("[keys]\nfly = f"), Err("line 2: unknown setting fly in [keys]".to_owned()));
+    assert_eq!(Config::parse("[colors]\ntheme = neon"), Err("line 2: unknown theme: neon".to_owned()));
+    assert_eq!(Config::parse("[colors]\npivots = red"), Err("line 2: unknown color: red".to_owned()));
+    assert_eq!(Config::parse("[numbers]\nscalar = f64"), Err("line 2: unknown setting scalar in [numbers]".to_owned()));
+    assert_eq!(Config::parse("[numbers]\nprecision = -1"), Err("line 2: invalid precision: -1".to_owned()));
+    assert_eq!(Config::parse("[export]\nformat = pdf"), Err("line 2: unknown export format: pdf".to_owned()));
+    assert_eq!(Config::parse("[export]\nsubspaces = yes"), Err("line 2: subspaces must be true or false, not yes".to_owned()));
+    assert_eq!(Config::parse("scale"), Err("line 1: expected name = value, found scale".to_owned()));
+
+    let mut config = Config::default();
+    assert_eq!(config.set_key(Action::Scale, 'i'), Err("i is already bound to swap".to_owned()));
+    assert_eq!(config.set_key(Action::Scale, 'S'), Ok(()));
+    assert_eq!(config.key(Action::Scale), 'S');
+}
+
+#[test]
+fn config_export() {
+    let m = Matrix::new(2, 2, vec![Ratio::new(1, 2), Ratio::from_integer(2),
+                                   Ratio::from_integer(-3), Ratio::from_integer(0)]);
+    assert_eq!(export(&m, ExportFormat::Plain), "1/2 2\n-3 0");
+    assert_eq!(export(&m, ExportFormat::Matlab), "[1/2 2; -3 0]");
+    assert_eq!(export(&m, ExportFormat::Json), "[[\"1/2\", 2], [-3, 0]]");
+    assert_eq!(export(&m, ExportFormat::Latex), "\\begin{bmatrix}\n\\frac{1}{2} & 2 \\\\\n-3 & 0\n\\end{bmatrix}");
+
+    // Everything but LaTeX can be pasted back in.
+    for format in [ExportFormat::Plain, ExportFormat::Matlab, ExportFormat::Json] {
+        assert_eq!(parse_matrix::<Ratio<i64>>(&export(&m, format)), Ok(m.clone()));
+    }
+}
+
+#[test]
+fn config_actions() {
+    for action in Action::all() {
+        assert_eq!(Action::from_name(action.name()), Some(action));
+        assert!(Action::menus().contains(&action.menu()), "{} isn't in a menu", action.name());
+    }
+    assert_eq!(Config::default().key(Action::Help), '?');
+}
@@ -0,0 +1,98 @@
+extern crate matrixops;
+extern crate num_rational;
+
+use matrixops::matrix::Matrix;
+use matrixops::polynomial::Polynomial;
+use num_rational::Ratio;
+
+fn ratios(v: &[i64]) -> Vec<Ratio<i64>> {
+    v.iter().map(|&x| Ratio::from_integer(x)).collect()
+}
+
+#[test]
+fn characteristic_polynomial() {
+    let m = Matrix::new(2, 2, ratios(&[
+        2, 1,
+        1, 2
+    ]));
+    // x^2 - 4x + 3
+    assert_eq!(m.characteristic_polynomial().unwrap(), Polynomial::new(ratios(&[3, -4, 1])));
+
+    let m = Matrix::new(3, 3, ratios(&[
+        1, 2, 3,
+        0, 4, 5,
+        0, 0, 6
+    ]));
+    // (x - 1)(x - 4)(x - 6)
+    assert_eq!(m.characteristic_polynomial().unwrap(), Polynomial::new(ratios(&[-24, 34, -11, 1])));
+}
+
+#[test]
+fn rational_eigenspaces() {
+    let m = Matrix::new(3, 3, ratios(&[
+        2, 0, 0,
+        0, 2, 0,
+        0, 0, 3
+    ]));
+    let spaces = m.rational_eigenspaces().unwrap();
+    assert_eq!(spaces.len(), 2);
+
+    let two = spaces.iter().find(|s| s.value == Ratio::from_integer(2)).unwrap();
+    assert_eq!(two.multiplicity, 2);
+    assert_eq!(two.basis, vec![ratios(&[1, 0, 0]), ratios(&[0, 1, 0])]);
+
+    let three = spaces.iter().find(|s| s.value == Ratio::from_integer(3)).unwrap();
+    assert_eq!(three.multiplicity, 1);
+    assert_eq!(three.basis, vec![ratios(&[0, 0, 1])]);
+}
+
+#[test]
+fn eigenvectors_defective() {
+    let m = Matrix::new(2, 2, ratios(&[
+        1, 1,
+        0, 1
+    ]));
+    assert_eq!(m.eigenvectors(&Ratio::from_integer(1)).unwrap(), vec![ratios(&[1, 0])]);
+    assert!(m.eigenvectors(&Ratio::from_integer(2)).unwrap().is_empty());
+}
+
+#[test]
+fn float_eigenvalues() {
+    let m = Matrix::new(3, 3, vec![
+        4.0, 1.0, 2.0,
+        1.0, 3.0, 0.0,
+        2.0, 0.0, 5.0
+    ]);
+    let mut values: Vec<f64> = m.eigenvalues().unwrap().iter().map(|v| v.re).collect();
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let poly = |x: f64| x * x * x - 12.0 * x * x + 42.0 * x - 43.0;
+    for v in values {
+        assert!(poly(v).abs() < 1e-8, "{} is not a root", v);
+    }
+
+    // A rotation has eigenvalues ±i.
+    let rotation = Matrix::new(2, 2, vec![
+        0.0, -1.0,
+        1.0, 0.0
+    ]);
+    let mut values = rotation.eigenvalues().unwrap();
+    values.sort_by(|a, b| a.im.partial_cmp(&b.im).unwrap());
+    assert!((values[0].im + 1.0).abs() < 1e-12 && values[0].re.abs() < 1e-12);
+    assert!((values[1].im - 1.0).abs() < 1e-12 && values[1].re.abs() < 1e-12);
+}
+
+#[test]
+fn hilbert_characteristic_polynomial() {
+    // The entries get too large for an i64 along the way unless they are
+    // kept as integers.
+    let h = Matrix::new(5, 5, (0..25).map(|i| Ratio::new(1, i / 5 + i % 5 + 1)).collect());
+    assert_eq!(h.characteristic_polynomial().unwrap(), Polynomial::new(vec![
+        Ratio::new(-1, 266716800000),
+        Ratio::new(61501, 53343360000),
+        Ratio::new(-852401, 222264000),
+        Ratio::new(735781, 2116800),
+        Ratio::new(-563, 315),
+        Ratio::from_integer(1)
+    ]));
+    assert!(h.rational_eigenspaces().unwrap().is_empty());
+}
@@ -0,0 +1,55 @@
+extern crate matrixops;
+extern crate num_rational;
+extern crate num_traits;
+
+use matrixops::exercise::{generate, worksheet, ExerciseOptions};
+use matrixops::latex;
+use matrixops::matrix::Matrix;
+use matrixops::random::Rng;
+use matrixops::reduce::{row_reduce, EchelonForm};
+use num_rational::Ratio;
+use num_traits::Signed;
+
+#[test]
+fn exercise_answers() {
+    let mut rng = Rng::new(2017);
+    for &(rows, cols, rank) in &[(3, 3, 3), (3, 4, 2), (4, 3, 1), (1, 3, 1), (2, 2, 0)] {
+        let options = ExerciseOptions { rank, max_entry: 5, ..ExerciseOptions::new(rows, cols) };
+        let exercise = generate(&options, &mut rng).unwrap();
+        let reduction = row_reduce(&exercise.problem).unwrap();
+        assert_eq!(reduction.matrix, exercise.answer);
+        assert_eq!(reduction.pivots, exercise.pivots);
+        assert_eq!(reduction.rank(), rank);
+        assert!(exercise.problem.rows().iter().flatten()
+            .all(|x| x.abs() <= Ratio::from_integer(5)));
+        assert!(exercise.answer.rows().iter().flatten().all(|x| x.is_integer()));
+        if rank > 0 {
+            assert!(exercise.problem.echelon_form() != EchelonForm::Reduced);
+        }
+    }
+}
+
+#[test]
+fn exercise_seed() {
+    let options = ExerciseOptions::new(3, 4);
+    assert_eq!(generate(&options, &mut Rng::new(5)), generate(&options, &mut Rng::new(5)));
+    assert!(generate(&ExerciseOptions { rank: 4, ..options }, &mut Rng::new(5)).is_err());
+    assert!(generate(&ExerciseOptions { max_entry: 0, ..options }, &mut Rng::new(5)).is_err());
+    // Gives up instead of returning a problem that isn't scrambled enough.
+    assert!(generate(&ExerciseOptions { steps: 1_000_000, ..options }, &mut Rng::new(5)).is_err());
+}
+
+#[test]
+fn latex_output() {
+    let m = Matrix::new(2, 2, vec![
+        Ratio::from_integer(1), Ratio::new(-1, 2),
+        Ratio::from_integer(-3), Ratio::new(5, 4)
+    ]);
+    assert_eq!(latex::matrix(&m),
+               "\\begin{bmatrix}\n1 & -\\frac{1}{2} \\\\\n-3 & \\frac{5}{4}\n\\end{bmatrix}");
+
+    let exercise = generate(&ExerciseOptions::new(2, 2), &mut Rng::new(1)).unwrap();
+    let doc = worksheet(&[exercise.clone(), exercise]);
+    assert!(doc.starts_with("\\documentclass"));
+    assert_eq!(doc.matches("\\item").count(), 4);
+}
@@ -0,0 +1,55 @@
+extern crate matrixops;
+extern crate num_rational;
+
+use matrixops::export::{export_with_subspaces, ExportFormat};
+use matrixops::matrix::Matrix;
+use num_rational::Ratio;
+
+fn matrix(rows: usize, cols: usize, data: Vec<i64>) -> Matrix<Ratio<i64>> {
+    Matrix::new(rows, cols, data.into_iter().map(Ratio::from_integer).collect())
+}
+
+#[test]
+fn export_subspaces() {
+    let m = matrix(2, 2, vec![2, 1,
+                              4, 2]);
+    assert_eq!(export_with_subspaces(&m, ExportFormat::Plain), Ok("2 1\n4 2\n\n\
+        pivot columns: 1\n\
+        column space: (2, 4)\n\
+        row space: (1, 1/2)\n\
+        null space: (-1/2, 1)\n\
+        left null space: (-2, 1)".to_owned()));
+    assert_eq!(export_with_subspaces(&m, ExportFormat::Matlab), Ok("A = [2 1; 4 2];\n\
+        pivot_columns = [1];\n\
+        column_space = [2; 4];\n\
+        row_space = [1; 1/2];\n\
+        null_space = [-1/2; 1];\n\
+        left_null_space = [-2; 1];".to_owned()));
+    assert_eq!(export_with_subspaces(&m, ExportFormat::Json), Ok("{\"matrix\": [[2, 1], [4, 2]], \
+        \"pivot_columns\": [1], \
+        \"column_space\": [[2, 4]], \
+        \"row_space\": [[1, \"1/2\"]], \
+        \"null_space\": [[\"-1/2\", 1]], \
+        \"left_null_space\": [[-2, 1]]}".to_owned()));
+    assert_eq!(export_with_subspaces(&m, ExportFormat::Latex), Ok("A = \\begin{bmatrix}\n2 & 1 \\\\\n4 & 2\n\\end{bmatrix}\n\n\
+        \\text{Pivot columns: } 1\n\n\
+        \\operatorname{Col}(A) = \\operatorname{span}\\left\\{ \\begin{bmatrix}\n2 \\\\\n4\n\\end{bmatrix} \\right\\}\n\n\
+        \\operatorname{Row}(A) = \\operatorname{span}\\left\\{ \\begin{bmatrix}\n1 \\\\\n\\frac{1}{2}\n\\end{bmatrix} \\right\\}\n\n\
+        \\operatorname{Nul}(A) = \\operatorname{span}\\left\\{ \\begin{bmatrix}\n-\\frac{1}{2} \\\\\n1\n\\end{bmatrix} \\right\\}\n\n\
+        \\operatorname{Nul}(A^T) = \\operatorname{span}\\left\\{ \\begin{bmatrix}\n-2 \\\\\n1\n\\end{bmatrix} \\right\\}".to_owned()));
+}
+
+#[test]
+fn export_trivial_subspaces() {
+    // The null spaces of an invertible matrix only contain zero.
+    let m = matrix(2, 2, vec![1, 0,
+                              0, 1]);
+    let plain = export_with_subspaces(&m, ExportFormat::Plain).unwrap();
+    assert!(plain.ends_with("null space: {0}\nleft null space: {0}"), "{}", plain);
+    let matlab = export_with_subspaces(&m, ExportFormat::Matlab).unwrap();
+    assert!(matlab.ends_with("null_space = zeros(2, 0);\nleft_null_space = zeros(2, 0);"), "{}", matlab);
+    let json = export_with_subspaces(&m, ExportFormat::Json).unwrap();
+    assert!(json.ends_with("\"null_space\": [], \"left_null_space\": []}"), "{}", json);
+    let latex = export_with_subspaces(&m, ExportFormat::Latex).unwrap();
+    assert!(latex.ends_with("\\operatorname{Nul}(A^T) = \\{\\mathbf{0}\\}"), "{}", latex);
+}
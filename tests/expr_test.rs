@@ -0,0 +1,54 @@
+extern crate matrixops;
+extern crate num_rational;
+
+use matrixops::expr::eval;
+use num_rational::Ratio;
+
+fn ratio(text: &str) -> Result<Ratio<i64>, String> {
+    eval(text)
+}
+
+#[test]
+fn expr_rational() {
+    assert_eq!(ratio("-3/4"), Ok(Ratio::new(-3, 4)));
+    assert_eq!(ratio("1/2 + 1/3"), Ok(Ratio::new(5, 6)));
+    assert_eq!(ratio("3/-6"), Ok(Ratio::new(-1, 2)));
+    assert_eq!(ratio("2^3"), Ok(Ratio::from_integer(8)));
+    assert_eq!(ratio("2^-2"), Ok(Ratio::new(1, 4)));
+    assert_eq!(ratio("2^3^2"), Ok(Ratio::from_integer(512)));
+    assert_eq!(ratio("-2^2"), Ok(Ratio::from_integer(-4)));
+    assert_eq!(ratio("(1 - 3) * (2 + 1/2)"), Ok(Ratio::from_integer(-5)));
+    assert_eq!(ratio("0.25"), Ok(Ratio::new(1, 4)));
+    assert_eq!(ratio("-1.5 * .2"), Ok(Ratio::new(-3, 10)));
+}
+
+#[test]
+fn expr_errors() {
+    assert!(ratio("").is_err());
+    assert!(ratio("1/0").is_err());
+    assert!(ratio("0^-1").is_err());
+    assert!(ratio("2^(1/2)").is_err());
+    assert!(ratio("sqrt(4)").is_err());
+    assert!(ratio("(1 + 2").is_err());
+    assert!(ratio("1 + ").is_err());
+    assert!(ratio("1 2").is_err());
+    assert!(ratio("1..2").is_err());
+    assert!(ratio("2 % 3").is_err());
+    assert!(ratio("10^30").is_err());
+
+    // The smallest i64 can't be negated.
+    let min = "(-9223372036854775807 - 1)";
+    assert_eq!(ratio(&format!("-{}", min)), Err("the result is too large".to_owned()));
+    assert_eq!(ratio(&format!("2^{}", min)), Err("the result is too large".to_owned()));
+    assert_eq!(ratio(&format!("{}^-1", min)), Err("the result is too large".to_owned()));
+    assert_eq!(ratio(&format!("{}^1", min)), Ok(Ratio::from_integer(i64::MIN)));
+    assert_eq!(ratio("2^(1/2)"), Err("exponents must be integers".to_owned()));
+}
+
+#[test]
+fn expr_float() {
+    assert_eq!(eval::<f64>("sqrt(2)^2 - 2").map(|x| x.abs() < 1e-12), Ok(true));
+    assert_eq!(eval::<f64>("2^0.5 * abs(-2)"), Ok(2f64.sqrt() * 2.0));
+    assert!(eval::<f64>("sqrt(-1)").is_err());
+    assert!(eval::<f64>("cos(1)").is_err());
+}
@@ -0,0 +1,38 @@
+// Needs the harness, which is only built with the `harness` feature.
+#![cfg(feature = "harness")]
+
+extern crate cursive;
+extern crate matrixops;
+
+use cursive::event::Key;
+use matrixops::ui::Form;
+use matrixops::ui::harness::Harness;
+use std::cell::Cell;
+use std::rc::Rc;
+
+#[test]
+fn form_submit() {
+    let mut h = Harness::new();
+    let layers = h.layers();
+    let sum = Rc::new(Cell::new(0));
+    let result = sum.clone();
+    Form::new("a and b")
+        .field::<usize>("get a")
+        .field_with("get b", |b: &usize| if *b > 0 { Ok(()) } else { Err("b can't be 0".to_owned()) })
+        .open(h.cursive(), move |_, values| {
+            let (a, b): (usize, usize) = (values.get(0), values.get(1));
+            result.set(a + b);
+        });
+    assert_eq!(h.layers(), layers + 1);
+
+    // b is invalid, so the form stays open.
+    h.submit_form(&["2", "0"]);
+    assert_eq!(h.layers(), layers + 1);
+    assert_eq!(sum.get(), 0);
+
+    h.press_key(Key::Backspace);
+    h.type_text("3");
+    h.press_key(Key::Enter);
+    assert_eq!(h.layers(), layers);
+    assert_eq!(sum.get(), 5);
+}
@@ -0,0 +1,51 @@
+extern crate matrixops;
+extern crate num_rational;
+
+use matrixops::ui::format::{decimal, pad_left, truncate, ColumnFormat};
+use num_rational::Ratio;
+
+#[test]
+fn format_align_fractions() {
+    let cells = ["-1/2", "3", "12/35", "-7"];
+    let fmt = ColumnFormat::new(cells.iter().cloned());
+    assert_eq!(fmt, ColumnFormat { left: 2, right: 3 });
+    let aligned: Vec<String> = cells.iter().map(|c| fmt.align(c)).collect();
+    assert_eq!(aligned, vec!["-1/2 ", " 3   ", "12/35", "-7   "]);
+}
+
+#[test]
+fn format_align_decimals() {
+    let cells = ["1.5", "-10.25", "3"];
+    let fmt = ColumnFormat::new(cells.iter().cloned());
+    let aligned: Vec<String> = cells.iter().map(|c| fmt.align(c)).collect();
+    assert_eq!(aligned, vec!["  1.5 ", "-10.25", "  3   "]);
+}
+
+#[test]
+fn format_empty_column() {
+    let fmt = ColumnFormat::new(Vec::new());
+    assert_eq!(fmt.width(), 0);
+    assert_eq!(fmt.align(""), "");
+}
+
+#[test]
+fn format_truncate() {
+    assert_eq!(truncate("12345", 5), "12345");
+    assert_eq!(truncate("123456", 5), "1234\u{2026}");
+    assert_eq!(truncate("\u{3bb}\u{3bb}", 2), "\u{3bb}\u{3bb}");
+    assert_eq!(truncate("abc", 0), "");
+}
+
+#[test]
+fn format_pad_left() {
+    assert_eq!(pad_left("12", 4), "  12");
+    assert_eq!(pad_left("12345", 4), "12345");
+}
+
+#[test]
+fn format_decimal() {
+    assert_eq!(decimal(&Ratio::new(1, 3), 3), "0.333");
+    assert_eq!(decimal(&Ratio::new(-5, 2), 2), "-2.50");
+    assert_eq!(decimal(&Ratio::new(7, 1), 0), "7");
+    assert_eq!(decimal(&Ratio::new(-1, 10000), 2), "0.00");
+}
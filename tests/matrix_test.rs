@@ -1,6 +1,10 @@
 extern crate matrixops;
+extern crate num_rational;
 
 use matrixops::matrix;
+use matrixops::random::Rng;
+use matrixops::reduce::row_reduce;
+use num_rational::Ratio;
 
 #[test]
 fn matrix_add() {
@@ -55,3 +59,215 @@ fn matrix_row_foreach() {
         0, 0, 1
     ]));
 }
+
+#[test]
+fn matrix_borrowing() {
+    let mut m = matrix::Matrix::new(2, 3, vec![
+        1, 2, 3,
+        4, 5, 6
+    ]);
+    assert_eq!(m.row(2), Some(&[4, 5, 6][..]));
+    assert_eq!(m.col_iter(3).unwrap().cloned().collect::<Vec<_>>(), vec![3, 6]);
+    assert_eq!(m.iter().sum::<i32>(), 21);
+
+    m.row_mut(1).unwrap()[0] = 7;
+    m[(1, 1)] = 8;
+    for x in m.iter_mut() {
+        *x *= 10;
+    }
+    assert_eq!(m, matrix::Matrix::new(2, 3, vec![
+        70, 20, 30,
+        40, 80, 60
+    ]));
+}
+
+#[test]
+fn matrix_bounds_read() {
+    let m = matrix::Matrix::new(2, 3, vec![
+        1, 2, 3,
+        4, 5, 6
+    ]);
+    assert_eq!(m.get(1, 1), Some(1));
+    assert_eq!(m.get(2, 3), Some(6));
+    for &(row, col) in &[(0, 1), (1, 0), (0, 0), (3, 1), (1, 4), (3, 4)] {
+        assert_eq!(m.get(row, col), None, "({}, {})", row, col);
+    }
+
+    assert_eq!(m.get_row(1), Some(vec![1, 2, 3]));
+    assert_eq!(m.get_row(2), Some(vec![4, 5, 6]));
+    assert_eq!(m.get_row(0), None);
+    assert_eq!(m.get_row(3), None);
+    assert!(m.row(0).is_none() && m.row(3).is_none());
+
+    assert_eq!(m.get_col(1), Some(vec![1, 4]));
+    assert_eq!(m.get_col(3), Some(vec![3, 6]));
+    assert_eq!(m.get_col(0), None);
+    assert_eq!(m.get_col(4), None);
+    assert!(m.col_iter(0).is_none() && m.col_iter(4).is_none());
+
+    assert!(m.has_row(1) && m.has_row(2) && !m.has_row(0) && !m.has_row(3));
+    assert!(m.has_col(1) && m.has_col(3) && !m.has_col(0) && !m.has_col(4));
+
+    let empty = matrix::Matrix::<i32>::new(0, 0, vec![]);
+    assert_eq!(empty.get(1, 1), None);
+    assert_eq!(empty.get_row(1), None);
+    assert_eq!(empty.get_col(1), None);
+    assert!(empty.rows().is_empty() && empty.cols().is_empty());
+
+    let wide = matrix::Matrix::<i32>::new(0, 3, vec![]);
+    for col in 1..4 {
+        assert_eq!(wide.col_iter(col).map(|c| c.count()), Some(0), "column {}", col);
+        assert_eq!(wide.get_col(col), Some(vec![]), "column {}", col);
+    }
+    assert!(wide.col_iter(4).is_none() && wide.get_col(4).is_none());
+    assert_eq!(wide.transpose(), matrix::Matrix::new(3, 0, vec![]));
+
+    let tall = matrix::Matrix::<i32>::new(3, 0, vec![]);
+    assert!(tall.col_iter(1).is_none() && tall.get_col(1).is_none());
+    assert_eq!(tall.get_row(3), Some(vec![]));
+    assert_eq!(tall.transpose(), matrix::Matrix::new(0, 3, vec![]));
+}
+
+#[test]
+fn matrix_bounds_write() {
+    let original = matrix::Matrix::new(2, 2, vec![
+        1, 2,
+        3, 4
+    ]);
+    let mut m = original.clone();
+    assert!(m.set(0, 1, 9).is_err());
+    assert!(m.set(1, 0, 9).is_err());
+    assert!(m.set(3, 1, 9).is_err());
+    assert!(m.set(1, 3, 9).is_err());
+    assert!(m.set_row(0, &[9, 9]).is_err());
+    assert!(m.set_row(3, &[9, 9]).is_err());
+    assert!(m.set_row(1, &[9]).is_err());
+    assert!(m.set_row(1, &[9, 9, 9]).is_err());
+    assert!(m.row_mut(0).is_none() && m.row_mut(3).is_none());
+    assert!(m.row_foreach(0, |x| x + 1).is_err());
+    assert!(m.row_foreach(3, |x| x + 1).is_err());
+    assert!(m.swap_rows(0, 1).is_err());
+    assert!(m.swap_rows(1, 3).is_err());
+    assert!(m.add_scaled_row(3, &1, 1).is_err());
+    assert!(m.add_scaled_row(1, &1, 0).is_err());
+    assert_eq!(m, original);
+
+    assert_eq!(m.set_row(3, &[9, 9]), Err("row 3 is out of bounds".to_owned()));
+    assert_eq!(m.set(1, 3, 9), Err("(1, 3) is out of bounds".to_owned()));
+
+    m.set(2, 2, 8).unwrap();
+    m.set_row(2, &[6, 7]).unwrap();
+    m.swap_rows(2, 2).unwrap();
+    assert_eq!(m, matrix::Matrix::new(2, 2, vec![
+        1, 2,
+        6, 7
+    ]));
+}
+
+#[test]
+fn matrix_index() {
+    let mut m = matrix::Matrix::new(2, 3, vec![
+        1, 2, 3,
+        4, 5, 6
+    ]);
+    assert_eq!(m[(0, 0)], 1);
+    assert_eq!(m[(1, 2)], 6);
+    assert_eq!(m[(1, 0)], m.get(2, 1).unwrap());
+    m[(0, 2)] = 9;
+    assert_eq!(m.get(1, 3), Some(9));
+}
+
+#[test]
+#[should_panic]
+fn matrix_index_row_out_of_bounds() {
+    let m = matrix::Matrix::new(2, 3, vec![0; 6]);
+    let _ = m[(2, 0)];
+}
+
+#[test]
+#[should_panic]
+fn matrix_index_col_out_of_bounds() {
+    let mut m = matrix::Matrix::new(2, 3, vec![0; 6]);
+    m[(0, 3)] = 1;
+}
+
+#[test]
+fn matrix_row_operations() {
+    let mut m = matrix::Matrix::new(3, 2, vec![
+        1, 2,
+        3, 4,
+        5, 6
+    ]);
+    m.swap_rows(1, 3).unwrap();
+    m.add_scaled_row(1, &2, 2).unwrap();
+    assert_eq!(m, matrix::Matrix::new(3, 2, vec![
+        5, 6,
+        13, 16,
+        1, 2
+    ]));
+    assert!(m.swap_rows(1, 4).is_err());
+    assert!(m.add_scaled_row(0, &1, 2).is_err());
+}
+
+#[test]
+fn matrix_diff() {
+    let m1 = matrix::Matrix::new(2, 2, vec![
+        1, 0,
+        0, 1
+    ]);
+    let m2 = matrix::Matrix::new(2, 2, vec![
+        1, 5,
+        0, 2
+    ]);
+    assert_eq!(m1.diff(&m2), vec![(1, 2), (2, 2)]);
+    assert!(m1.diff(&m1).is_empty());
+}
+
+#[test]
+fn matrix_templates() {
+    assert_eq!(matrix::Matrix::<i32>::zero(1, 2), matrix::Matrix::new(1, 2, vec![0, 0]));
+    assert_eq!(matrix::Matrix::identity(2), matrix::Matrix::new(2, 2, vec![1, 0, 0, 1]));
+    assert_eq!(matrix::Matrix::diagonal(&[2, 3]), matrix::Matrix::new(2, 2, vec![2, 0, 0, 3]));
+    assert_eq!(matrix::Matrix::tridiagonal(3, -1, 2, -1), matrix::Matrix::new(3, 3, vec![
+         2, -1,  0,
+        -1,  2, -1,
+         0, -1,  2
+    ]));
+    assert_eq!(matrix::Matrix::vandermonde(&[2, 3, 4]), matrix::Matrix::new(3, 3, vec![
+        1, 2, 4,
+        1, 3, 9,
+        1, 4, 16
+    ]));
+    assert_eq!(matrix::Matrix::<Ratio<i64>>::hilbert(2), matrix::Matrix::new(2, 2, vec![
+        Ratio::from_integer(1), Ratio::new(1, 2),
+        Ratio::new(1, 2), Ratio::new(1, 3)
+    ]));
+}
+
+#[test]
+fn matrix_random() {
+    let m = matrix::Matrix::<i64>::random_integers(3, 4, -2, 5, &mut Rng::new(7));
+    assert_eq!((m.rows, m.cols), (3, 4));
+    assert!(m.rows().iter().flatten().all(|&x| (-2..=5).contains(&x)));
+    assert_eq!(m, matrix::Matrix::random_integers(3, 4, -2, 5, &mut Rng::new(7)));
+
+    let mut rng = Rng::new(42);
+    for rank in 0..4 {
+        let m = matrix::Matrix::<Ratio<i64>>::random_with_rank(3, 5, rank, 3, &mut rng).unwrap();
+        assert_eq!(row_reduce(&m).unwrap().rank(), rank);
+    }
+    assert!(matrix::Matrix::<Ratio<i64>>::random_with_rank(2, 5, 3, 3, &mut rng).is_err());
+
+    let m = matrix::Matrix::<Ratio<i64>>::random_with_integer_inverse(4, 3, &mut rng);
+    let det = m.characteristic_polynomial().unwrap().coeffs()[0];
+    assert!(det == Ratio::from_integer(1) || det == Ratio::from_integer(-1));
+
+    // Ranges as wide as i64 don't overflow.
+    let (min, max) = (i64::MIN, i64::MAX);
+    for &(lo, hi) in &[(min, max), (min, 0), (-1, max), (min, min), (max, max)] {
+        for _ in 0..100 {
+            let x = rng.range(lo, hi);
+            assert!(x >= lo && x <= hi, "{} is not in {}..{}", x, lo, hi);
+        }
+    }
+}
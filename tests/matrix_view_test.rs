@@ -0,0 +1,32 @@
+extern crate cursive;
+extern crate matrixops;
+extern crate num_rational;
+
+use cursive::traits::View;
+use cursive::vec::Vec2;
+use matrixops::matrix::Matrix;
+use matrixops::ui::MatrixView;
+use matrixops::ui::command::Command;
+use num_rational::Ratio;
+
+#[test]
+fn matrix_view_long_entries() {
+    // "1/12345678901234567" is wider than a truncated cell, but fits on the
+    // screen.
+    let mut view = MatrixView::new(Matrix::new(1, 1, vec![Ratio::new(1i64, 12345678901234567)]));
+    view.set_highlight_changes(false);
+    assert_eq!(view.required_size(Vec2::new(80, 24)), Vec2::new(22, 2));
+    assert_eq!(view.required_size(Vec2::new(10, 24)), Vec2::new(10, 2));
+}
+
+#[test]
+fn matrix_view_stable_size() {
+    let mut view = MatrixView::new(Matrix::new(2, 2, vec![1, 2, 3, 4].into_iter().map(Ratio::from_integer).collect()));
+    let size = view.required_size(Vec2::new(80, 24));
+    assert!(!view.is_highlighting());
+
+    // The command shown next to the rows doesn't make the view wider.
+    view.apply_command(Command::AddRow { coeff: Ratio::from_integer(-3), src: 1, dest: 2 }).unwrap();
+    assert!(view.is_highlighting());
+    assert_eq!(view.required_size(Vec2::new(80, 24)), size);
+}
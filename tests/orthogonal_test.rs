@@ -0,0 +1,80 @@
+extern crate matrixops;
+extern crate num_rational;
+
+use matrixops::matrix::Matrix;
+use matrixops::orthogonal::Projection;
+use num_rational::Ratio;
+
+fn assert_close(a: &Matrix<f64>, b: &Matrix<f64>) {
+    assert_eq!((a.rows, a.cols), (b.rows, b.cols));
+    for i in 1..(a.rows + 1) {
+        for j in 1..(a.cols + 1) {
+            let (x, y) = (a.get(i, j).unwrap(), b.get(i, j).unwrap());
+            assert!((x - y).abs() < 1e-9, "({}, {}): {} != {}", i, j, x, y);
+        }
+    }
+}
+
+#[test]
+fn orthogonalize_rational() {
+    let r = |n| Ratio::from_integer(n);
+    let m = Matrix::new(3, 2, vec![
+        r(1), r(1),
+        r(1), r(0),
+        r(0), r(1)
+    ]);
+    let (basis, steps) = m.orthogonalize().unwrap();
+    assert_eq!(basis, Matrix::new(3, 2, vec![
+        r(1), Ratio::new(1, 2),
+        r(1), Ratio::new(-1, 2),
+        r(0), r(1)
+    ]));
+    assert_eq!(steps, vec![Projection { coeff: Ratio::new(1, 2), src: 1, dest: 2 }]);
+    assert_eq!(steps[0].apply(&m).unwrap(), basis);
+}
+
+#[test]
+fn orthogonalize_dependent() {
+    let m = Matrix::new(2, 2, vec![
+        Ratio::from_integer(1), Ratio::from_integer(2),
+        Ratio::from_integer(2), Ratio::from_integer(4)
+    ]);
+    assert!(m.orthogonalize().is_err());
+}
+
+#[test]
+fn gram_schmidt_variants() {
+    let m = Matrix::new(3, 2, vec![
+        3.0, 0.6,
+        4.0, 5.8,
+        0.0, 4.0
+    ]);
+    let expected = Matrix::new(3, 2, vec![
+        0.6, -0.48,
+        0.8, 0.36,
+        0.0, 0.8
+    ]);
+    assert_close(&m.gram_schmidt().unwrap(), &expected);
+    assert_close(&m.modified_gram_schmidt().unwrap(), &expected);
+}
+
+#[test]
+fn qr_decomposition() {
+    let m = Matrix::new(3, 2, vec![
+        3.0, 0.6,
+        4.0, 5.8,
+        0.0, 4.0
+    ]);
+    let (q, r) = m.qr().unwrap();
+    assert_close(&r, &Matrix::new(2, 2, vec![
+        5.0, 5.0,
+        0.0, 5.0
+    ]));
+    let mut product = Vec::new();
+    for i in 1..4 {
+        for j in 1..3 {
+            product.push((1..3).map(|k| q.get(i, k).unwrap() * r.get(k, j).unwrap()).sum());
+        }
+    }
+    assert_close(&Matrix::new(3, 2, product), &m);
+}
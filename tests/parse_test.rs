@@ -0,0 +1,42 @@
+extern crate matrixops;
+extern crate num_rational;
+
+use matrixops::matrix::Matrix;
+use matrixops::parse::parse_matrix;
+use num_rational::Ratio;
+
+#[test]
+fn parse_plain() {
+    let expected = Matrix::new(2, 3, vec![1, 2, 3, 4, 5, 6]);
+    assert_eq!(parse_matrix::<i32>("1 2 3\n4 5 6").unwrap(), expected);
+    assert_eq!(parse_matrix::<i32>("  1, 2, 3\n\n 4,5,6 \n").unwrap(), expected);
+    assert_eq!(parse_matrix::<i32>("1 2 3; 4 5 6").unwrap(), expected);
+    assert_eq!(parse_matrix::<i32>("1\t2\t3\r\n4\t5\t6").unwrap(), expected);
+}
+
+#[test]
+fn parse_matlab() {
+    let expected = Matrix::new(2, 2, vec![1, -2, 3, 4]);
+    assert_eq!(parse_matrix::<i32>("[1 -2; 3 4]").unwrap(), expected);
+    assert_eq!(parse_matrix::<i32>("[1, -2\n 3, 4]").unwrap(), expected);
+}
+
+#[test]
+fn parse_json() {
+    let expected = Matrix::new(2, 2, vec![1, -2, 3, 4]);
+    assert_eq!(parse_matrix::<i32>("[[1, -2], [3, 4]]").unwrap(), expected);
+    assert_eq!(parse_matrix::<i32>("[\n  [1,-2],\n  [3,4]\n]").unwrap(), expected);
+
+    let fractions = parse_matrix::<Ratio<i64>>("[[\"1/2\", \"-3\"]]").unwrap();
+    assert_eq!(fractions, Matrix::new(1, 2, vec![Ratio::new(1, 2), Ratio::from_integer(-3)]));
+}
+
+#[test]
+fn parse_errors() {
+    assert!(parse_matrix::<i32>("").is_err());
+    assert!(parse_matrix::<i32>("[]").is_err());
+    assert_eq!(parse_matrix::<i32>("1 2\n3").unwrap_err(), "row 2 has 1 entries, but row 1 has 2");
+    assert_eq!(parse_matrix::<i32>("1 2\n3 x").unwrap_err(), "entry (2, 2) \"x\": not a number");
+    assert!(parse_matrix::<i32>("[[1, 2], [3, 4]").is_err());
+    assert!(parse_matrix::<i32>("[[1, 2] 5 [3, 4]]").is_err());
+}
@@ -0,0 +1,51 @@
+extern crate matrixops;
+extern crate num_rational;
+
+use matrixops::polynomial::Polynomial;
+use num_rational::Ratio;
+
+#[test]
+fn polynomial_display() {
+    let p = Polynomial::new(vec![
+        Ratio::new(-1, 2), Ratio::from_integer(0), Ratio::new(3, 4), Ratio::from_integer(-1)
+    ]);
+    assert_eq!(p.to_string(), "-x^3 + (3/4)x^2 - 1/2");
+    assert_eq!(Polynomial::new(vec![Ratio::from_integer(0)]).to_string(), "0");
+}
+
+#[test]
+fn polynomial_rational_roots() {
+    // (x - 1/2)^2 (x + 3) x
+    let factors = [
+        Polynomial::new(vec![Ratio::new(-1, 2), Ratio::from_integer(1)]),
+        Polynomial::new(vec![Ratio::new(-1, 2), Ratio::from_integer(1)]),
+        Polynomial::new(vec![Ratio::from_integer(3), Ratio::from_integer(1)]),
+        Polynomial::new(vec![Ratio::from_integer(0), Ratio::from_integer(1)])
+    ];
+    let p = factors.iter().fold(Polynomial::new(vec![Ratio::from_integer(1)]), |acc, f| &acc * f);
+    let mut roots = p.rational_roots().unwrap();
+    roots.sort();
+    assert_eq!(roots, vec![
+        (Ratio::from_integer(-3), 1),
+        (Ratio::from_integer(0), 1),
+        (Ratio::new(1, 2), 2)
+    ]);
+
+    // x^2 - 2 has no rational roots.
+    let irrational = Polynomial::new(vec![Ratio::from_integer(-2), Ratio::from_integer(0), Ratio::from_integer(1)]);
+    assert!(irrational.rational_roots().unwrap().is_empty());
+}
+
+#[test]
+fn polynomial_large_roots() {
+    // 9223372036854775783 is the largest prime that fits in an i64.
+    let prime = 9223372036854775783;
+    let p = Polynomial::new(vec![Ratio::from_integer(-prime), Ratio::from_integer(1)]);
+    assert_eq!(p.rational_roots(), Ok(vec![(Ratio::from_integer(prime), 1)]));
+    let p = Polynomial::new(vec![Ratio::from_integer(prime), Ratio::from_integer(0), Ratio::from_integer(1)]);
+    assert_eq!(p.rational_roots(), Ok(vec![]));
+
+    // The product of two primes above 2^21 can't be factored.
+    let p = Polynomial::new(vec![Ratio::from_integer(4294967291 * 2147483647), Ratio::from_integer(1)]);
+    assert!(p.rational_roots().is_err());
+}
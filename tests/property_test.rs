@@ -0,0 +1,265 @@
+extern crate matrixops;
+extern crate num_rational;
+extern crate num_traits;
+
+use matrixops::export::{export, ExportFormat};
+use matrixops::matrix::Matrix;
+use matrixops::parse::parse_matrix;
+use matrixops::random::Rng;
+use matrixops::session::Session;
+use matrixops::ui::ColumnLabels;
+use matrixops::ui::command::{ApplyMode, Command, CommandSequence};
+use num_rational::Ratio;
+use num_traits::{One, Zero};
+use std::str::FromStr;
+
+type Q = Ratio<i64>;
+
+/// How many random cases each property is checked on. Case `i` uses seed
+/// `i`, so failures can be reproduced.
+const CASES: u64 = 200;
+
+fn ratio(rng: &mut Rng) -> Q {
+    Ratio::new(rng.range(-6, 6), rng.range(1, 4))
+}
+
+fn nonzero_ratio(rng: &mut Rng) -> Q {
+    Ratio::new(rng.nonzero_range(-6, 6), rng.range(1, 4))
+}
+
+fn random_matrix(rng: &mut Rng, rows: usize, cols: usize) -> Matrix<Q> {
+    let mut m = Matrix::new(rows, cols, (0..(rows * cols)).map(|_| ratio(rng)).collect());
+    // Make some matrices rank deficient, which random entries rarely are.
+    if rows > 1 && rng.range(0, 3) == 0 {
+        let copy = m.get_row(1).unwrap();
+        m.set_row(rows, &copy).unwrap();
+    }
+    m
+}
+
+fn random_size(rng: &mut Rng) -> (usize, usize) {
+    (rng.range(1, 4) as usize, rng.range(1, 4) as usize)
+}
+
+fn random_row(rng: &mut Rng, rows: usize) -> usize {
+    rng.range(1, rows as i64) as usize
+}
+
+/// A random `ScaleRow`, `SwapRow` or `AddRow` that can be undone.
+fn random_row_operation(rng: &mut Rng, rows: usize) -> Command<Q> {
+    match rng.range(0, 2) {
+        0 => Command::ScaleRow { coeff: nonzero_ratio(rng), row: random_row(rng, rows) },
+        1 => Command::SwapRow { row1: random_row(rng, rows), row2: random_row(rng, rows) },
+        _ if rows == 1 => Command::SwapRow { row1: 1, row2: 1 },
+        _ => {
+            let src = random_row(rng, rows);
+            let dest = (src + rng.range(1, rows as i64 - 1) as usize - 1) % rows + 1;
+            Command::AddRow { coeff: ratio(rng), src, dest }
+        }
+    }
+}
+
+/// A random command of any kind, which may not apply to the matrix.
+fn random_command(rng: &mut Rng, rows: usize, cols: usize) -> Command<Q> {
+    match rng.range(0, 5) {
+        0 => Command::EditCell { row: random_row(rng, rows), col: random_row(rng, cols), value: ratio(rng) },
+        1 => Command::EditRow { row: random_row(rng, rows), values: (0..cols).map(|_| ratio(rng)).collect() },
+        2 => Command::EliminateColumn { pivot_row: random_row(rng, rows), col: random_row(rng, cols) },
+        3 => Command::NormalizePivot { row: random_row(rng, rows) },
+        4 => {
+            let mut order: Vec<usize> = (1..(rows + 1)).collect();
+            rng.shuffle(&mut order);
+            Command::PermuteRows(order)
+        },
+        _ => random_row_operation(rng, rows)
+    }
+}
+
+/// The determinant by the Leibniz formula, as a check that doesn't row
+/// reduce.
+fn leibniz(m: &Matrix<Q>) -> Q {
+    fn permutations(n: usize) -> Vec<Vec<usize>> {
+        if n == 0 {
+            return vec![vec![]];
+        }
+        permutations(n - 1).into_iter()
+            .flat_map(|p| (0..n).map(move |i| {
+                let mut p = p.clone();
+                p.insert(i, n);
+                p
+            }))
+            .collect()
+    }
+    permutations(m.rows).into_iter()
+        .map(|p| {
+            let product = p.iter().enumerate()
+                .fold(Q::one(), |acc, (r, &c)| acc * m.get(r + 1, c).unwrap());
+            if sign(&p) < 0 { -product } else { product }
+        })
+        .fold(Q::zero(), |acc, x| acc + x)
+}
+
+/// The sign of a permutation of `1..n`, from its number of inversions.
+fn sign(order: &[usize]) -> i64 {
+    let inversions = (0..order.len())
+        .flat_map(|i| (i + 1..order.len()).map(move |j| (i, j)))
+        .filter(|&(i, j)| order[i] > order[j])
+        .count();
+    if inversions % 2 == 0 { 1 } else { -1 }
+}
+
+/// The cofactor of `(row, col)`, which is the determinant after the row is
+/// replaced by the unit vector for the column.
+fn cofactor(m: &Matrix<Q>, row: usize, col: usize) -> Q {
+    let unit: Vec<Q> = (1..(m.cols + 1)).map(|c| if c == col { Q::one() } else { Q::zero() }).collect();
+    let mut m = m.clone();
+    m.set_row(row, &unit).unwrap();
+    m.determinant().unwrap()
+}
+
+#[test]
+fn property_determinant() {
+    for seed in 0..CASES {
+        let mut rng = Rng::new(seed);
+        let n = rng.range(1, 4) as usize;
+        let m = random_matrix(&mut rng, n, n);
+        assert_eq!(m.determinant(), Ok(leibniz(&m)), "seed {}: {:?}", seed, m);
+        assert_eq!(m.transpose().determinant(), m.determinant(), "seed {}", seed);
+    }
+    assert!(Matrix::<Q>::identity(2).determinant().is_ok());
+    assert!(Matrix::<Q>::zero(2, 3).determinant().is_err());
+}
+
+#[test]
+fn property_row_operations_preserve_rank() {
+    for seed in 0..CASES {
+        let mut rng = Rng::new(seed);
+        let (rows, cols) = random_size(&mut rng);
+        let m = random_matrix(&mut rng, rows, cols);
+        let cmd = random_row_operation(&mut rng, rows);
+        let upd = cmd.apply(&m).unwrap();
+        assert_eq!(upd.rank(), m.rank(), "seed {}: {}", seed, cmd);
+    }
+}
+
+#[test]
+fn property_swap_twice() {
+    for seed in 0..CASES {
+        let mut rng = Rng::new(seed);
+        let (rows, cols) = random_size(&mut rng);
+        let m = random_matrix(&mut rng, rows, cols);
+        let swap = Command::SwapRow { row1: random_row(&mut rng, rows), row2: random_row(&mut rng, rows) };
+        assert_eq!(swap.apply(&swap.apply(&m).unwrap()), Ok(m), "seed {}: {}", seed, swap);
+    }
+}
+
+#[test]
+fn property_scale_and_unscale() {
+    for seed in 0..CASES {
+        let mut rng = Rng::new(seed);
+        let (rows, cols) = random_size(&mut rng);
+        let m = random_matrix(&mut rng, rows, cols);
+        let row = random_row(&mut rng, rows);
+        let coeff = nonzero_ratio(&mut rng);
+        let scaled = Command::ScaleRow { coeff, row }.apply(&m).unwrap();
+        let unscaled = Command::ScaleRow { coeff: coeff.recip(), row }.apply(&scaled);
+        assert_eq!(unscaled, Ok(m), "seed {}", seed);
+    }
+}
+
+#[test]
+fn property_determinant_under_commands() {
+    for seed in 0..CASES {
+        let mut rng = Rng::new(seed);
+        let n = rng.range(1, 4) as usize;
+        let m = random_matrix(&mut rng, n, n);
+        let det = m.determinant().unwrap();
+        let cmd = if rng.range(0, 1) == 0 { random_row_operation(&mut rng, n) } else { random_command(&mut rng, n, n) };
+        let upd = match cmd.apply(&m) {
+            Ok(upd) => upd,
+            // Only composite commands depend on the matrix.
+            Err(_) => {
+                assert!(cmd.is_composite(), "seed {}: {}", seed, cmd);
+                continue;
+            }
+        };
+
+        let expected = match cmd {
+            Command::SwapRow { row1, row2 } if row1 != row2 => -det,
+            Command::SwapRow { .. } | Command::AddRow { .. } | Command::EliminateColumn { .. } => det,
+            Command::ScaleRow { coeff, .. } => det * coeff,
+            Command::EditCell { row, col, value } =>
+                det + (value - m.get(row, col).unwrap()) * cofactor(&m, row, col),
+            Command::EditRow { row, ref values } => values.iter()
+                .enumerate()
+                .fold(Q::zero(), |acc, (c, v)| acc + *v * cofactor(&m, row, c + 1)),
+            Command::NormalizePivot { row } => {
+                let lead = m.get_row(row).unwrap().into_iter().find(|x| !x.is_zero()).unwrap();
+                det / lead
+            },
+            Command::PermuteRows(ref order) => det * Q::from_integer(sign(order))
+        };
+        assert_eq!(upd.determinant(), Ok(expected), "seed {}: {} on {:?}", seed, cmd, m);
+    }
+}
+
+#[test]
+fn property_apply_in_place() {
+    for seed in 0..CASES {
+        let mut rng = Rng::new(seed);
+        let (rows, cols) = random_size(&mut rng);
+        let m = random_matrix(&mut rng, rows, cols);
+        // Rows and columns past the end check that failures change nothing.
+        let cmd = random_command(&mut rng, rows + 1, cols + 1);
+        let mut upd = m.clone();
+        match cmd.apply_in_place(&mut upd, ApplyMode::Strict) {
+            Ok(()) => assert_eq!(Ok(upd), cmd.apply_with(&m, ApplyMode::Strict), "seed {}: {}", seed, cmd),
+            Err(e) => {
+                assert_eq!(Err(e), cmd.apply_with(&m, ApplyMode::Strict), "seed {}: {}", seed, cmd);
+                assert_eq!(upd, m, "seed {}: {}", seed, cmd);
+            }
+        }
+    }
+}
+
+#[test]
+fn property_command_text() {
+    for seed in 0..CASES {
+        let mut rng = Rng::new(seed);
+        let (rows, cols) = random_size(&mut rng);
+        let cmd = random_command(&mut rng, rows, cols);
+        assert_eq!(Command::from_str(&cmd.to_string()), Ok(cmd.clone()), "seed {}: {}", seed, cmd);
+    }
+}
+
+#[test]
+fn property_session_and_sequence_text() {
+    for seed in 0..CASES {
+        let mut rng = Rng::new(seed);
+        let (rows, cols) = random_size(&mut rng);
+        let history: Vec<Command<Q>> = (0..rng.range(0, 5)).map(|_| random_command(&mut rng, rows, cols)).collect();
+
+        let session = Session {
+            matrix: random_matrix(&mut rng, rows, cols),
+            history: history.clone(),
+            column_labels: if rng.range(0, 1) == 0 { ColumnLabels::Numbered } else { ColumnLabels::Variables },
+            highlight_changes: rng.range(0, 1) == 0
+        };
+        assert_eq!(Session::from_text(&session.to_text()), Ok(session), "seed {}", seed);
+
+        let seq = CommandSequence { name: format!("sequence {}", seed), commands: history };
+        assert_eq!(CommandSequence::parse_all(&seq.to_text()), Ok(vec![seq]), "seed {}", seed);
+    }
+}
+
+#[test]
+fn property_export_and_parse() {
+    for seed in 0..CASES {
+        let mut rng = Rng::new(seed);
+        let (rows, cols) = random_size(&mut rng);
+        let m = random_matrix(&mut rng, rows, cols);
+        for format in [ExportFormat::Plain, ExportFormat::Matlab, ExportFormat::Json] {
+            assert_eq!(parse_matrix(&export(&m, format)), Ok(m.clone()), "seed {}: {:?}", seed, format);
+        }
+    }
+}
@@ -0,0 +1,89 @@
+extern crate matrixops;
+extern crate num_rational;
+
+use matrixops::matrix::Matrix;
+use matrixops::quiz::{load_scores, rref_mistakes, save_score, Quiz, Score};
+use matrixops::reduce::EchelonForm;
+use matrixops::ui::command::Command;
+use num_rational::Ratio;
+use std::env;
+use std::fs;
+
+fn ratios(v: &[i64]) -> Vec<Ratio<i64>> {
+    v.iter().map(|&x| Ratio::from_integer(x)).collect()
+}
+
+#[test]
+fn quiz_moves() {
+    let problem = Matrix::new(2, 2, ratios(&[2, 4, 1, 3]));
+    let mut quiz = Quiz::new(problem.clone()).unwrap();
+
+    let scale_by_zero = Command::ScaleRow { coeff: Ratio::from_integer(0), row: 1 };
+    let add_to_self = Command::AddRow { coeff: Ratio::from_integer(1), src: 2, dest: 2 };
+    let edit = Command::EditCell { row: 1, col: 1, value: Ratio::from_integer(1) };
+    for cmd in &[scale_by_zero, add_to_self, edit] {
+        assert!(quiz.check_move(cmd).is_err());
+        quiz.record_invalid_move();
+    }
+
+    let steps = vec![
+        Command::ScaleRow { coeff: Ratio::new(1, 2), row: 1 },
+        Command::AddRow { coeff: Ratio::from_integer(-1), src: 1, dest: 2 }
+    ];
+    let mut matrix = problem;
+    for cmd in &steps {
+        quiz.check_move(cmd).unwrap();
+        matrix = cmd.apply(&matrix).unwrap();
+        quiz.record_move();
+    }
+    assert_eq!(quiz.check(&matrix), vec!["Column 2 should be zero in row 1, since row 2 has its pivot there"]);
+
+    let last = Command::AddRow { coeff: Ratio::from_integer(-2), src: 2, dest: 1 };
+    quiz.check_move(&last).unwrap();
+    matrix = last.apply(&matrix).unwrap();
+    quiz.record_move();
+    assert!(quiz.check(&matrix).is_empty());
+    assert_eq!((quiz.moves(), quiz.invalid_moves()), (3, 3));
+
+    let score = quiz.score(1500000000, true);
+    assert_eq!((score.checks, score.solved), (2, true));
+
+    // A quiz that is left unsolved is scored too.
+    let mut quiz = Quiz::new(Matrix::new(2, 2, ratios(&[2, 4, 1, 3]))).unwrap();
+    quiz.record_move();
+    let score = quiz.score(1500000000, false);
+    assert_eq!((score.moves, score.invalid_moves, score.checks, score.solved), (1, 0, 0, false));
+}
+
+#[test]
+fn quiz_mistakes() {
+    let matrices = vec![
+        (ratios(&[1, 0, 2, 0, 0, 0, 0, 1, 3]), 1),
+        (ratios(&[0, 2, 1, 1, 0, 0, 0, 0, 0]), 2),
+        (ratios(&[1, 0, 2, 0, 1, 3, 0, 0, 0]), 0)
+    ];
+    for (data, count) in matrices {
+        let m = Matrix::new(3, 3, data);
+        let mistakes = rref_mistakes(&m);
+        assert_eq!(mistakes.len(), count, "{:?}", mistakes);
+        assert_eq!(mistakes.is_empty(), m.echelon_form() == EchelonForm::Reduced);
+    }
+}
+
+#[test]
+fn quiz_scores_file() {
+    let path = env::temp_dir().join(format!("matrops-scores-{}", std::process::id())).join("scores.tsv");
+    let _ = fs::remove_file(&path);
+    assert_eq!(load_scores(&path), Ok(vec![]));
+
+    let scores = vec![
+        Score { time: 1, rows: 3, cols: 4, moves: 7, optimal_moves: 6, invalid_moves: 1, checks: 2, solved: true },
+        Score { time: 2, rows: 2, cols: 2, moves: 1, optimal_moves: 3, invalid_moves: 0, checks: 1, solved: false }
+    ];
+    for score in &scores {
+        save_score(&path, score).unwrap();
+    }
+    assert_eq!(load_scores(&path), Ok(scores));
+    assert!(Score::from_line("1\t2\t3").is_err());
+    fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
@@ -0,0 +1,85 @@
+extern crate matrixops;
+extern crate num_rational;
+
+use matrixops::matrix::Matrix;
+use matrixops::reduce::{row_reduce, EchelonForm};
+use num_rational::Ratio;
+
+fn ratios(v: &[i64]) -> Vec<Ratio<i64>> {
+    v.iter().map(|&x| Ratio::from_integer(x)).collect()
+}
+
+#[test]
+fn reduce_to_rref() {
+    let m = Matrix::new(3, 4, ratios(&[
+        0, 2, 4, 2,
+        1, 1, 1, 1,
+        2, 4, 6, 4
+    ]));
+    let reduction = row_reduce(&m).unwrap();
+    assert_eq!(reduction.matrix, Matrix::new(3, 4, ratios(&[
+        1, 0, -1, 0,
+        0, 1, 2, 1,
+        0, 0, 0, 0
+    ])));
+    assert_eq!(reduction.pivots, vec![1, 2]);
+    assert_eq!(reduction.free_columns(), vec![3, 4]);
+
+    let replayed = reduction.steps.iter().fold(m, |acc, cmd| cmd.apply(&acc).unwrap());
+    assert_eq!(replayed, reduction.matrix);
+}
+
+#[test]
+fn reduce_kernel_basis() {
+    let m = Matrix::new(2, 3, ratios(&[
+        1, 2, 3,
+        2, 4, 6
+    ]));
+    let reduction = row_reduce(&m).unwrap();
+    assert_eq!(reduction.rank(), 1);
+    assert_eq!(reduction.kernel_basis(), vec![ratios(&[-2, 1, 0]), ratios(&[-3, 0, 1])]);
+}
+
+#[test]
+fn fundamental_subspaces() {
+    let m = Matrix::new(3, 3, ratios(&[
+        1, 2, 3,
+        2, 4, 7,
+        3, 6, 10
+    ]));
+    assert_eq!(m.pivot_columns().unwrap(), vec![1, 3]);
+    assert_eq!(m.column_space().unwrap(), vec![ratios(&[1, 2, 3]), ratios(&[3, 7, 10])]);
+    assert_eq!(m.row_space().unwrap(), vec![ratios(&[1, 2, 0]), ratios(&[0, 0, 1])]);
+    assert_eq!(m.null_space().unwrap(), vec![ratios(&[-2, 1, 0])]);
+    assert_eq!(m.left_null_space().unwrap(), vec![ratios(&[-1, -1, 1])]);
+}
+
+#[test]
+fn echelon_form() {
+    let rref = Matrix::new(3, 3, ratios(&[
+        1, 2, 0,
+        0, 0, 1,
+        0, 0, 0
+    ]));
+    assert_eq!(rref.echelon_form(), EchelonForm::Reduced);
+    assert_eq!(rref.leading_entries(), vec![Some(1), Some(3), None]);
+
+    let reff = Matrix::new(3, 3, ratios(&[
+        2, 2, 1,
+        0, 0, 3,
+        0, 0, 0
+    ]));
+    assert_eq!(reff.echelon_form(), EchelonForm::Echelon);
+
+    let zero_row_first = Matrix::new(2, 2, ratios(&[
+        0, 0,
+        1, 0
+    ]));
+    assert_eq!(zero_row_first.echelon_form(), EchelonForm::Neither);
+
+    let same_column = Matrix::new(2, 2, ratios(&[
+        1, 0,
+        1, 1
+    ]));
+    assert_eq!(same_column.echelon_form(), EchelonForm::Neither);
+}
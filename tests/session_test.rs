@@ -0,0 +1,52 @@
+extern crate matrixops;
+extern crate num_rational;
+
+use matrixops::matrix::Matrix;
+use matrixops::session::Session;
+use matrixops::ui::ColumnLabels;
+use matrixops::ui::command::Command;
+use num_rational::Ratio;
+use std::env;
+use std::fs;
+
+fn session() -> Session<Ratio<i64>> {
+    Session {
+        matrix: Matrix::new(2, 3, vec![
+            Ratio::from_integer(1), Ratio::new(-1, 2), Ratio::from_integer(0),
+            Ratio::from_integer(0), Ratio::from_integer(1), Ratio::new(7, 3)
+        ]),
+        history: vec![
+            Command::SwapRow { row1: 1, row2: 2 },
+            Command::AddRow { coeff: Ratio::new(-1, 2), src: 1, dest: 2 },
+            Command::EliminateColumn { pivot_row: 2, col: 2 }
+        ],
+        column_labels: ColumnLabels::Variables,
+        highlight_changes: false
+    }
+}
+
+#[test]
+fn session_text() {
+    let session = session();
+    let text = session.to_text();
+    assert!(text.contains("1 -1/2 0\n0 1 7/3\n"));
+    assert_eq!(Session::from_text(&text), Ok(session));
+
+    let empty = Session { history: vec![], ..self::session() };
+    assert_eq!(Session::from_text(&empty.to_text()), Ok(empty));
+    assert!(Session::<Ratio<i64>>::from_text("labels: numbered\n").is_err());
+    assert!(Session::<Ratio<i64>>::from_text("").is_err());
+}
+
+#[test]
+fn session_file() {
+    let dir = env::temp_dir().join(format!("matrops-session-{}", std::process::id()));
+    let path = dir.join("session.txt");
+    assert_eq!(Session::<Ratio<i64>>::load(&path), Ok(None));
+
+    let session = session();
+    session.save(&path).unwrap();
+    session.save(&path).unwrap();
+    assert_eq!(Session::load(&path), Ok(Some(session)));
+    fs::remove_dir_all(dir).unwrap();
+}
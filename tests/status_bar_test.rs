@@ -0,0 +1,37 @@
+extern crate matrixops;
+extern crate num_rational;
+
+use matrixops::config::Notation;
+use matrixops::matrix::Matrix;
+use matrixops::ui::{MatrixView, StatusBar};
+use matrixops::ui::command::Command;
+use num_rational::Ratio;
+
+#[test]
+fn status_bar_text() {
+    let mut bar = StatusBar::new();
+    assert_eq!(bar.text(), "rational | fractions");
+
+    let mut view = MatrixView::new(Matrix::new(2, 3, vec![2, 0, 4, 0, 1, 1].into_iter().map(Ratio::from_integer).collect()));
+    bar.update(&view);
+    assert_eq!(bar.text(), "2x3 | rational | fractions | REF");
+
+    view.apply_command(Command::ScaleRow { row: 1, coeff: Ratio::new(1, 2) }).unwrap();
+    bar.update(&view);
+    bar.set_notation(Notation::Decimal, 4);
+    bar.set_unsaved(true);
+    bar.set_recording(true);
+    assert_eq!(bar.text(), "2x3 | rational | 4 decimals | RREF | last: R1 *= 1/2 | unsaved | recording");
+
+    bar.set_quiz(Some((1, 0)));
+    assert_eq!(bar.text(), "2x3 | rational | 4 decimals | RREF | last: R1 *= 1/2 | moves: 1 | unsaved | recording");
+    bar.set_quiz(Some((3, 2)));
+    assert!(bar.text().contains("| moves: 3 (2 invalid) |"), "{}", bar.text());
+
+    bar.clear();
+    bar.set_recording(false);
+    assert!(!bar.has_message());
+    bar.error("Couldn't save the session");
+    assert!(bar.has_message());
+    assert_eq!(bar.text(), "rational | 4 decimals | Couldn't save the session");
+}
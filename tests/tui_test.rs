@@ -0,0 +1,127 @@
+// Needs the harness, which is only built with the `harness` feature.
+#![cfg(feature = "harness")]
+
+extern crate cursive;
+extern crate matrixops;
+extern crate num_rational;
+
+use cursive::event::Key;
+use matrixops::config::Config;
+use matrixops::matrix::Matrix;
+use matrixops::ui::Action;
+use matrixops::ui::harness::Harness;
+use num_rational::Ratio;
+
+fn matrix(rows: usize, cols: usize, data: Vec<i64>) -> Matrix<Ratio<i64>> {
+    Matrix::new(rows, cols, data.into_iter().map(Ratio::from_integer).collect())
+}
+
+#[test]
+fn tui_row_operations() {
+    let mut h = Harness::new();
+    h.open(matrix(2, 2, vec![2, 4, 1, 3]));
+
+    h.press('s');
+    h.submit_form(&["1", "1/2"]);
+    assert_eq!(h.matrix(), matrix(2, 2, vec![1, 2, 1, 3]));
+
+    h.press('a');
+    h.submit_form(&["1", "-1", "2"]);
+    assert_eq!(h.matrix(), matrix(2, 2, vec![1, 2, 0, 1]));
+
+    h.press('i');
+    h.submit_form(&["1", "2"]);
+    assert_eq!(h.matrix(), matrix(2, 2, vec![0, 1, 1, 2]));
+    assert!(h.status().contains("last: R1 <-> R2"), "status: {}", h.status());
+}
+
+#[test]
+fn tui_invalid_form() {
+    let mut h = Harness::new();
+    h.open(matrix(2, 2, vec![0, 1, 1, 0]));
+
+    // Row 3 is out of bounds, so the form stays open.
+    let layers = h.layers();
+    h.press('i');
+    h.submit_form(&["1", "3"]);
+    assert_eq!(h.layers(), layers + 1);
+    assert_eq!(h.matrix(), matrix(2, 2, vec![0, 1, 1, 0]));
+
+    // Fixing the row submits it.
+    h.press_key(Key::Backspace);
+    h.type_text("2");
+    h.press_key(Key::Enter);
+    assert_eq!(h.layers(), layers);
+    assert_eq!(h.matrix(), matrix(2, 2, vec![1, 0, 0, 1]));
+}
+
+#[test]
+fn tui_error_popup() {
+    let mut h = Harness::new();
+    h.open(matrix(2, 2, vec![0, 1, 1, 0]));
+    h.press('c');
+    h.submit_form(&["1", "1"]);
+    assert_eq!(h.error(), Some("Error: the pivot (1, 1) is zero".to_owned()));
+    assert_eq!(h.matrix(), matrix(2, 2, vec![0, 1, 1, 0]));
+}
+
+#[test]
+fn tui_remapped_keys() {
+    let mut config = Config::default();
+    config.set_key(Action::Scale, 'm').unwrap();
+    let mut h = Harness::with_config(config);
+    h.open(Matrix::identity(2));
+
+    h.press('s');
+    h.press('m');
+    h.submit_form(&["2", "3"]);
+    assert_eq!(h.matrix(), matrix(2, 2, vec![1, 0, 0, 3]));
+
+    h.press('?');
+    let help = h.text("help_text").expect("help not shown");
+    assert!(help.contains("  m  Multiply a row by a scalar"), "help: {}", help);
+}
+
+#[test]
+fn tui_export() {
+    let mut h = Harness::new();
+    h.open(matrix(2, 2, vec![1, 2, 3, 4]));
+    h.press('o');
+    assert_eq!(h.text("export_text"), Some("1 2\n3 4".to_owned()));
+}
+
+#[test]
+fn tui_export_subspaces() {
+    let mut config = Config::default();
+    config.export_subspaces = true;
+    let mut h = Harness::with_config(config);
+    h.open(matrix(1, 2, vec![1, 2]));
+    h.press('o');
+    assert_eq!(h.text("export_text"), Some("1 2\n\n\
+        pivot columns: 1\n\
+        column space: (1)\n\
+        row space: (1, 2)\n\
+        null space: (-2, 1)\n\
+        left null space: {0}".to_owned()));
+}
+
+#[test]
+fn tui_replay_all_or_nothing() {
+    let mut h = Harness::new();
+    h.open(matrix(2, 2, vec![1, 0, 0, 1]));
+    h.press('r');
+    h.press('i');
+    h.submit_form(&["1", "2"]);
+    h.press('c');
+    h.submit_form(&["2", "1"]);
+    h.press('r');
+    h.submit_form(&["swap and clear"]);
+
+    // The swap works on this matrix, but then the pivot is zero.
+    h.open(matrix(2, 2, vec![0, 1, 0, 2]));
+    h.press('p');
+    h.press_key(Key::Enter);
+    h.submit_form(&[""]);
+    assert_eq!(h.error(), Some("Error: step 2 (clear C1 using R2): the pivot (2, 1) is zero".to_owned()));
+    assert_eq!(h.matrix(), matrix(2, 2, vec![0, 1, 0, 2]));
+}